@@ -3,16 +3,23 @@
 /// Implements the revolutionary capability-based security model for RustOS,
 /// replacing traditional syscalls with typed, async capability channels.
 
+use core::cell::UnsafeCell;
 use core::fmt;
 use core::future::Future;
+use core::hint;
+use core::ops::{Deref, DerefMut};
 use core::pin::Pin;
-use core::task::{Context, Poll};
+use core::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use core::task::{Context, Poll, Waker};
+use alloc::collections::{BTreeMap, VecDeque};
+use alloc::sync::Arc;
 use alloc::vec::Vec;
 use alloc::string::{String, ToString};
+use alloc::format;
 use serde::{Deserialize, Serialize};
 
 /// Unique capability identifier
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
 pub struct CapabilityId(pub u64);
 
 /// Permission set for capabilities
@@ -54,6 +61,8 @@ pub struct Capability {
     pub resource: ResourceHandle,
     /// Expiration time (optional)
     pub expires_at: Option<u64>, // Unix timestamp
+    /// The capability this one was delegated from, if any
+    pub derived_from: Option<CapabilityId>,
 }
 
 /// Capability request
@@ -124,6 +133,8 @@ pub enum CapabilityError {
     InvalidRequest,
     SystemError(String),
     Expired,
+    /// A peer declared a protocol version we don't speak
+    UnsupportedVersion { have: u16, want: u16 },
 }
 
 impl fmt::Display for CapabilityError {
@@ -134,45 +145,384 @@ impl fmt::Display for CapabilityError {
             CapabilityError::InvalidRequest => write!(f, "Invalid capability request"),
             CapabilityError::SystemError(msg) => write!(f, "System error: {}", msg),
             CapabilityError::Expired => write!(f, "Capability expired"),
+            CapabilityError::UnsupportedVersion { have, want } => {
+                write!(f, "Unsupported protocol version: peer speaks {}, we speak {}", have, want)
+            }
+        }
+    }
+}
+
+/// Wire protocol version for the capability channel's framed encoding
+///
+/// Bump this whenever `CapabilityRequest`/`CapabilityResponse`'s wire
+/// representation changes in a backward-incompatible way. A client and
+/// kernel built at different times exchange this during the channel
+/// handshake, so a mismatch fails explicitly instead of misinterpreting
+/// the other side's frames.
+pub const PROTOCOL_VERSION: u16 = 1;
+
+/// A peer's declared protocol version, sent as the first frame over any
+/// transport (e.g. a future virtio channel) before either side trusts
+/// subsequent request/response frames
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+struct ProtocolHandshake {
+    version: u16,
+}
+
+/// Check a peer's declared protocol version against ours
+fn check_protocol_version(peer_version: u16) -> Result<(), CapabilityError> {
+    if peer_version != PROTOCOL_VERSION {
+        return Err(CapabilityError::UnsupportedVersion { have: peer_version, want: PROTOCOL_VERSION });
+    }
+    Ok(())
+}
+
+/// Frame a value for the wire: a little-endian `u32` length prefix followed by its postcard encoding
+fn encode_frame<T: Serialize>(value: &T) -> Result<Vec<u8>, CapabilityError> {
+    let body = postcard::to_allocvec(value)
+        .map_err(|e| CapabilityError::SystemError(format!("encode failed: {}", e)))?;
+    let mut frame = Vec::with_capacity(4 + body.len());
+    frame.extend_from_slice(&(body.len() as u32).to_le_bytes());
+    frame.extend_from_slice(&body);
+    Ok(frame)
+}
+
+/// Decode a single length-prefixed frame produced by `encode_frame`
+fn decode_frame<T: for<'de> Deserialize<'de>>(frame: &[u8]) -> Result<T, CapabilityError> {
+    let len_bytes: [u8; 4] = frame.get(0..4).ok_or(CapabilityError::InvalidRequest)?.try_into().unwrap();
+    let len = u32::from_le_bytes(len_bytes) as usize;
+    let body = frame.get(4..4 + len).ok_or(CapabilityError::InvalidRequest)?;
+    postcard::from_bytes(body).map_err(|e| CapabilityError::SystemError(format!("decode failed: {}", e)))
+}
+
+/// Encode this channel's protocol version as the handshake frame a client sends first
+fn encode_handshake() -> Vec<u8> {
+    encode_frame(&ProtocolHandshake { version: PROTOCOL_VERSION }).expect("handshake encoding cannot fail")
+}
+
+/// Decode a peer's handshake frame and verify its protocol version matches ours
+fn decode_handshake(frame: &[u8]) -> Result<(), CapabilityError> {
+    let handshake: ProtocolHandshake = decode_frame(frame)?;
+    check_protocol_version(handshake.version)
+}
+
+/// Frame a `CapabilityRequest` for the wire
+pub fn encode_request(request: &CapabilityRequest) -> Result<Vec<u8>, CapabilityError> {
+    encode_frame(request)
+}
+
+/// Decode a framed `CapabilityRequest`
+pub fn decode_request(frame: &[u8]) -> Result<CapabilityRequest, CapabilityError> {
+    decode_frame(frame)
+}
+
+/// Frame a `CapabilityResponse` for the wire
+pub fn encode_response(response: &CapabilityResponse) -> Result<Vec<u8>, CapabilityError> {
+    encode_frame(response)
+}
+
+/// Decode a framed `CapabilityResponse`
+pub fn decode_response(frame: &[u8]) -> Result<CapabilityResponse, CapabilityError> {
+    decode_frame(frame)
+}
+
+#[test_case]
+fn request_response_frames_round_trip() {
+    let request = CapabilityRequest::Memory(MemoryRequest::Allocate { size: 4096 });
+    let request_frame = encode_request(&request).expect("encode_request");
+    let decoded = decode_request(&request_frame).expect("decode_request");
+    match decoded {
+        CapabilityRequest::Memory(MemoryRequest::Allocate { size }) => assert_eq!(size, 4096),
+        other => panic!("decoded into the wrong request variant: {other:?}"),
+    }
+
+    let response = CapabilityResponse::Error(CapabilityError::UnsupportedVersion { have: 1, want: 2 });
+    let response_frame = encode_response(&response).expect("encode_response");
+    let decoded = decode_response(&response_frame).expect("decode_response");
+    match decoded {
+        CapabilityResponse::Error(CapabilityError::UnsupportedVersion { have, want }) => {
+            assert_eq!((have, want), (1, 2));
+        }
+        other => panic!("decoded into the wrong response variant: {other:?}"),
+    }
+
+    // A truncated frame must fail to decode rather than reading past the buffer
+    assert!(decode_request(&request_frame[..request_frame.len() - 1]).is_err());
+}
+
+/// Returns `true` if `narrowed` refers to a resource no broader than `parent`
+///
+/// Delegation may only attenuate a resource, never widen it: a narrowed
+/// `File` path must live under the parent's path, and every other resource
+/// kind must match the parent exactly.
+fn resource_is_narrowing(parent: &ResourceHandle, narrowed: &ResourceHandle) -> bool {
+    match (parent, narrowed) {
+        (ResourceHandle::File(parent_path), ResourceHandle::File(child_path)) => {
+            let parent_path = parent_path.trim_end_matches('/');
+            let parent_prefix = format!("{parent_path}/");
+            child_path == parent_path || child_path.starts_with(parent_prefix.as_str())
+        }
+        (ResourceHandle::Network(a), ResourceHandle::Network(b)) => a == b,
+        (ResourceHandle::Memory(a), ResourceHandle::Memory(b)) => a == b,
+        (ResourceHandle::Process(a), ResourceHandle::Process(b)) => a == b,
+        (ResourceHandle::Device(a), ResourceHandle::Device(b)) => a == b,
+        _ => false,
+    }
+}
+
+#[test_case]
+fn resource_is_narrowing_rejects_sibling_directory() {
+    let parent = ResourceHandle::File(String::from("/home/user"));
+
+    // A sibling directory that merely shares a string prefix must not be
+    // accepted as a narrowed child of "/home/user".
+    assert!(!resource_is_narrowing(&parent, &ResourceHandle::File(String::from("/home/user-evil"))));
+    assert!(!resource_is_narrowing(&parent, &ResourceHandle::File(String::from("/home/userXYZ"))));
+
+    // Real children, the parent itself, and a trailing-slash parent all still narrow correctly.
+    assert!(resource_is_narrowing(&parent, &ResourceHandle::File(String::from("/home/user/docs"))));
+    assert!(resource_is_narrowing(&parent, &ResourceHandle::File(String::from("/home/user"))));
+    assert!(resource_is_narrowing(
+        &ResourceHandle::File(String::from("/home/user/")),
+        &ResourceHandle::File(String::from("/home/user/docs"))
+    ));
+}
+
+/// Minimal no_std spinlock used to guard the capability channel's shared queue
+///
+/// There's no executor-provided mutex available in this environment, so we
+/// spin; critical sections here are just `VecDeque`/`BTreeMap` bookkeeping
+/// and stay short.
+struct SpinLock<T> {
+    locked: AtomicBool,
+    value: UnsafeCell<T>,
+}
+
+unsafe impl<T: Send> Send for SpinLock<T> {}
+unsafe impl<T: Send> Sync for SpinLock<T> {}
+
+impl<T> SpinLock<T> {
+    const fn new(value: T) -> Self {
+        Self { locked: AtomicBool::new(false), value: UnsafeCell::new(value) }
+    }
+
+    fn lock(&self) -> SpinLockGuard<'_, T> {
+        while self
+            .locked
+            .compare_exchange_weak(false, true, Ordering::Acquire, Ordering::Relaxed)
+            .is_err()
+        {
+            hint::spin_loop();
         }
+        SpinLockGuard { lock: self }
+    }
+}
+
+struct SpinLockGuard<'a, T> {
+    lock: &'a SpinLock<T>,
+}
+
+impl<'a, T> Deref for SpinLockGuard<'a, T> {
+    type Target = T;
+    fn deref(&self) -> &T {
+        unsafe { &*self.lock.value.get() }
+    }
+}
+
+impl<'a, T> DerefMut for SpinLockGuard<'a, T> {
+    fn deref_mut(&mut self) -> &mut T {
+        unsafe { &mut *self.lock.value.get() }
     }
 }
 
-/// Capability channel for async communication (simplified for no_std)
+impl<'a, T> Drop for SpinLockGuard<'a, T> {
+    fn drop(&mut self) {
+        self.lock.locked.store(false, Ordering::Release);
+    }
+}
+
+/// Request/response bookkeeping shared between a `CapabilityChannel` and its `CapabilityChannelService`
+struct ChannelQueue {
+    /// Requests in their framed wire encoding, as `CapabilityChannel::send`
+    /// produced them; the service decodes each one off the queue, the same
+    /// way it would off a real transport
+    requests: VecDeque<(u64, Option<CapabilityId>, Vec<u8>)>,
+    /// Responses in their framed wire encoding, decoded back into a
+    /// `CapabilityResponse` by the waiting `ResponseWaiter`
+    responses: BTreeMap<u64, Vec<u8>>,
+    wakers: BTreeMap<u64, Waker>,
+}
+
+impl ChannelQueue {
+    fn new() -> Self {
+        Self {
+            requests: VecDeque::new(),
+            responses: BTreeMap::new(),
+            wakers: BTreeMap::new(),
+        }
+    }
+}
+
+/// Future returned by [`CapabilityChannel::request`], resolving once the
+/// service has produced a response tagged with this request's sequence number
+struct ResponseWaiter {
+    seq: u64,
+    queue: Arc<SpinLock<ChannelQueue>>,
+}
+
+impl Future for ResponseWaiter {
+    /// The framed response bytes, still to be decoded by the caller
+    type Output = Vec<u8>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let mut queue = self.queue.lock();
+        if let Some(response) = queue.responses.remove(&self.seq) {
+            Poll::Ready(response)
+        } else {
+            queue.wakers.insert(self.seq, cx.waker().clone());
+            Poll::Pending
+        }
+    }
+}
+
+/// Capability channel for async communication (client side)
+///
+/// A single-producer/single-consumer pair with its `CapabilityChannelService`,
+/// sharing a request/response queue. Multiple requests may be in flight at
+/// once; each is tagged with a monotonically increasing sequence number so
+/// responses can be matched back to the right waiter.
 pub struct CapabilityChannel {
-    // Simplified channel implementation for no_std environment
-    // In a full implementation, this would use proper async channels
+    queue: Arc<SpinLock<ChannelQueue>>,
+    next_seq: Arc<AtomicU64>,
 }
 
 impl CapabilityChannel {
-    /// Create a new capability channel
+    /// Create a new, standalone capability channel (not backed by a capability registry)
     pub fn new() -> (Self, CapabilityChannelService) {
-        let channel = CapabilityChannel {};
-        let service = CapabilityChannelService {};
-        
+        Self::with_registry(None)
+    }
+
+    /// Create a new capability channel whose service validates referenced
+    /// capabilities against the given registry
+    ///
+    /// Both ends of an in-process channel are built from the same
+    /// `PROTOCOL_VERSION`, so this self-handshake can never actually fail —
+    /// it only exercises the encode/decode path the same way a real peer
+    /// would. Genuine version skew can only arise once a channel's two ends
+    /// are built at different times, e.g. a client connecting over the
+    /// virtio transport this protocol is meant to ride on; that path should
+    /// decode the peer's declared version off the wire and call
+    /// `CapabilityChannelService::negotiate` with it directly, rather than
+    /// going through this constructor.
+    fn with_registry(registry: Option<Arc<SpinLock<CapabilityRegistry>>>) -> (Self, CapabilityChannelService) {
+        decode_handshake(&encode_handshake()).expect("local channel handshake always matches its own version");
+
+        let queue = Arc::new(SpinLock::new(ChannelQueue::new()));
+        let channel = CapabilityChannel {
+            queue: queue.clone(),
+            next_seq: Arc::new(AtomicU64::new(1)),
+        };
+        let service = CapabilityChannelService { queue, registry };
+
         (channel, service)
     }
-    
-    /// Send a capability request (simplified)
-    pub async fn request(&mut self, _request: CapabilityRequest) -> Result<CapabilityResponse, CapabilityError> {
-        // TODO: Implement proper async request/response mechanism
-        // For now, return a placeholder error
-        Err(CapabilityError::SystemError("Not implemented".to_string()))
+
+    /// Protocol version this channel was built against
+    pub fn protocol_version(&self) -> u16 {
+        PROTOCOL_VERSION
+    }
+
+    /// Send a capability request and asynchronously wait for its response
+    pub async fn request(&mut self, request: CapabilityRequest) -> Result<CapabilityResponse, CapabilityError> {
+        self.send(None, request).await
+    }
+
+    /// Send a capability request that acts on behalf of an existing
+    /// capability. A channel from `CapabilitySystem::create_channel` has its
+    /// service validate it (existence + expiration) before dispatching; a
+    /// standalone channel from `CapabilityChannel::new` has no registry and
+    /// dispatches without that check.
+    pub async fn request_with_capability(
+        &mut self,
+        capability: CapabilityId,
+        request: CapabilityRequest,
+    ) -> Result<CapabilityResponse, CapabilityError> {
+        self.send(Some(capability), request).await
+    }
+
+    async fn send(
+        &mut self,
+        capability: Option<CapabilityId>,
+        request: CapabilityRequest,
+    ) -> Result<CapabilityResponse, CapabilityError> {
+        let seq = self.next_seq.fetch_add(1, Ordering::SeqCst);
+        let frame = encode_request(&request)?;
+
+        {
+            let mut queue = self.queue.lock();
+            queue.requests.push_back((seq, capability, frame));
+        }
+
+        let response_frame = ResponseWaiter { seq, queue: self.queue.clone() }.await;
+        decode_response(&response_frame)
     }
 }
 
-/// Capability channel service (kernel side) - simplified
-pub struct CapabilityChannelService {}
+/// Capability channel service (kernel side)
+pub struct CapabilityChannelService {
+    queue: Arc<SpinLock<ChannelQueue>>,
+    registry: Option<Arc<SpinLock<CapabilityRegistry>>>,
+}
 
 impl CapabilityChannelService {
-    /// Process capability requests (simplified)
+    /// Check a peer's declared protocol version before trusting any framed
+    /// request it sends, e.g. the first frame read off a transport such as
+    /// the virtio channel this capability protocol is meant to ride on
+    pub fn negotiate(&self, peer_version: u16) -> Result<(), CapabilityError> {
+        check_protocol_version(peer_version)
+    }
+
+    /// Drain and process every request currently queued, waking the client
+    /// future waiting on each one
     pub async fn process_requests(&mut self) {
-        // TODO: Implement proper request processing
-        // This is a placeholder for the simplified implementation
+        loop {
+            let next = {
+                let mut queue = self.queue.lock();
+                queue.requests.pop_front()
+            };
+
+            let (seq, capability, frame) = match next {
+                Some(item) => item,
+                None => break,
+            };
+
+            let response = match decode_request(&frame) {
+                Ok(request) => self.handle_request(capability, request).await,
+                Err(error) => CapabilityResponse::Error(error),
+            };
+            let response_frame = encode_response(&response).unwrap_or_else(|error| {
+                encode_response(&CapabilityResponse::Error(error)).expect("encoding an Error response cannot fail")
+            });
+
+            let mut queue = self.queue.lock();
+            queue.responses.insert(seq, response_frame);
+            if let Some(waker) = queue.wakers.remove(&seq) {
+                waker.wake();
+            }
+        }
     }
-    
-    /// Handle a single capability request
-    async fn handle_request(&self, request: CapabilityRequest) -> CapabilityResponse {
+
+    /// Handle a single capability request, validating the referenced
+    /// capability (if any) before dispatching to the specific handler
+    async fn handle_request(&self, capability: Option<CapabilityId>, request: CapabilityRequest) -> CapabilityResponse {
+        if let Some(capability_id) = capability {
+            if let Some(registry) = &self.registry {
+                if let Err(error) = registry.lock().validate(capability_id) {
+                    return CapabilityResponse::Error(error);
+                }
+            }
+        }
+
         match request {
             CapabilityRequest::FileSystem(fs_req) => {
                 self.handle_filesystem_request(fs_req).await
@@ -201,10 +551,47 @@ impl CapabilityChannelService {
         CapabilityResponse::Error(CapabilityError::SystemError("Not implemented".to_string()))
     }
     
-    /// Handle memory requests
-    async fn handle_memory_request(&self, _request: MemoryRequest) -> CapabilityResponse {
-        // TODO: Implement memory capability handling
-        CapabilityResponse::Error(CapabilityError::SystemError("Not implemented".to_string()))
+    /// Handle memory requests. Charges allocations against the calling
+    /// process (the owner of the currently scheduled thread), so a
+    /// process that requests more than its configured `memory_limit`
+    /// gets turned back with `PermissionDenied` rather than silently
+    /// granted the memory.
+    async fn handle_memory_request(&self, request: MemoryRequest) -> CapabilityResponse {
+        match request {
+            MemoryRequest::Allocate { size } => {
+                let Some(process_manager) = crate::process::get_process_manager() else {
+                    return CapabilityResponse::Error(CapabilityError::SystemError("process manager not initialized".to_string()));
+                };
+
+                let pid = process_manager
+                    .get_current_thread()
+                    .and_then(|tid| process_manager.get_thread(tid))
+                    .map(|thread| thread.pid);
+                let Some(pid) = pid else {
+                    return CapabilityResponse::Error(CapabilityError::SystemError("no running process to charge the allocation to".to_string()));
+                };
+
+                let region = crate::memory::MemoryRegion {
+                    start: 0,
+                    size,
+                    permissions: crate::memory::MemoryPermissions { read: true, write: true, execute: false },
+                    backing: crate::memory::MemoryBacking::Physical,
+                };
+
+                match process_manager.allocate_memory(pid, region) {
+                    Ok(()) => match process_manager.resource_usage(pid) {
+                        Ok(usage) => CapabilityResponse::Data(usage.memory_used.to_le_bytes().to_vec()),
+                        Err(error) => CapabilityResponse::Error(CapabilityError::SystemError(format!("{error:?}"))),
+                    },
+                    Err(crate::process::ProcessError::ResourceExhausted) => CapabilityResponse::Error(CapabilityError::PermissionDenied),
+                    Err(error) => CapabilityResponse::Error(CapabilityError::SystemError(format!("{error:?}"))),
+                }
+            }
+            // TODO: Implement deallocate/map capability handling
+            MemoryRequest::Deallocate { .. } | MemoryRequest::Map { .. } => {
+                CapabilityResponse::Error(CapabilityError::SystemError("Not implemented".to_string()))
+            }
+        }
     }
     
     /// Handle process requests
@@ -214,13 +601,142 @@ impl CapabilityChannelService {
     }
 }
 
+/// Registry of live capabilities, shared between `CapabilitySystem` and every
+/// `CapabilityChannelService` so request handling can validate against it
+struct CapabilityRegistry {
+    next_capability_id: u64,
+    /// Every live capability, keyed by id
+    capabilities: BTreeMap<CapabilityId, Capability>,
+    /// Parent capability id -> delegated child capability ids
+    children: BTreeMap<CapabilityId, Vec<CapabilityId>>,
+}
+
+impl CapabilityRegistry {
+    fn new() -> Self {
+        Self {
+            next_capability_id: 1,
+            capabilities: BTreeMap::new(),
+            children: BTreeMap::new(),
+        }
+    }
+
+    fn next_capability_id(&mut self) -> CapabilityId {
+        let id = CapabilityId(self.next_capability_id);
+        self.next_capability_id += 1;
+        id
+    }
+
+    fn register(&mut self, capability: Capability) {
+        self.capabilities.insert(capability.id, capability);
+    }
+
+    fn get(&self, id: CapabilityId) -> Option<&Capability> {
+        self.capabilities.get(&id)
+    }
+
+    /// Delegate a narrowed subset of `parent`'s permissions and resource to a new capability
+    ///
+    /// The parent must hold `Permission::Delegate`. Delegation can only
+    /// attenuate: `subset` must be contained in the parent's permissions,
+    /// and `resource_narrowing`, if given, must be no broader than the
+    /// parent's resource. The new capability inherits the parent's
+    /// expiration and is recorded as its child so it can be transitively
+    /// revoked later.
+    fn delegate(
+        &mut self,
+        parent: CapabilityId,
+        subset: PermissionSet,
+        resource_narrowing: Option<ResourceHandle>,
+    ) -> Result<Capability, CapabilityError> {
+        let parent_capability = self
+            .capabilities
+            .get(&parent)
+            .ok_or(CapabilityError::ResourceNotFound)?;
+
+        if !parent_capability.permissions.permissions.contains(&Permission::Delegate) {
+            return Err(CapabilityError::PermissionDenied);
+        }
+
+        for permission in &subset.permissions {
+            if !parent_capability.permissions.permissions.contains(permission) {
+                return Err(CapabilityError::PermissionDenied);
+            }
+        }
+
+        let resource = match resource_narrowing {
+            Some(narrowed) => {
+                if !resource_is_narrowing(&parent_capability.resource, &narrowed) {
+                    return Err(CapabilityError::InvalidRequest);
+                }
+                narrowed
+            }
+            None => parent_capability.resource.clone(),
+        };
+
+        let child = Capability {
+            id: self.next_capability_id(),
+            permissions: subset,
+            resource,
+            expires_at: parent_capability.expires_at,
+            derived_from: Some(parent),
+        };
+
+        self.children.entry(parent).or_insert_with(Vec::new).push(child.id);
+        self.capabilities.insert(child.id, child.clone());
+
+        Ok(child)
+    }
+
+    /// Revoke a capability and transitively invalidate every capability delegated from it
+    fn revoke(&mut self, id: CapabilityId) {
+        let descendants = self.children.remove(&id).unwrap_or_default();
+        self.capabilities.remove(&id);
+
+        for descendant in descendants {
+            self.revoke(descendant);
+        }
+    }
+
+    /// Validate that a capability is still live: it must exist in the
+    /// registry and, if it carries an expiration, not have passed it yet
+    fn validate(&self, id: CapabilityId) -> Result<(), CapabilityError> {
+        let capability = self.capabilities.get(&id).ok_or(CapabilityError::ResourceNotFound)?;
+
+        if let Some(expires_at) = capability.expires_at {
+            if crate::time::now_unix() > expires_at {
+                return Err(CapabilityError::Expired);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Drop every capability whose expiration has passed, cascading to their
+    /// delegated descendants. Returns the number of capabilities swept.
+    fn sweep_expired(&mut self) -> usize {
+        let now = crate::time::now_unix();
+        let expired: Vec<CapabilityId> = self
+            .capabilities
+            .iter()
+            .filter(|(_, capability)| capability.expires_at.map_or(false, |expires_at| now > expires_at))
+            .map(|(id, _)| *id)
+            .collect();
+
+        let count = expired.len();
+        for id in expired {
+            self.revoke(id);
+        }
+        count
+    }
+}
+
 /// Global capability system
 static mut CAPABILITY_SYSTEM: Option<CapabilitySystem> = None;
 
 /// Capability system manager
 pub struct CapabilitySystem {
     channels: Vec<CapabilityChannelService>,
-    next_capability_id: u64,
+    registry: Arc<SpinLock<CapabilityRegistry>>,
 }
 
 impl CapabilitySystem {
@@ -228,35 +744,70 @@ impl CapabilitySystem {
     pub fn new() -> Self {
         Self {
             channels: Vec::new(),
-            next_capability_id: 1,
+            registry: Arc::new(SpinLock::new(CapabilityRegistry::new())),
         }
     }
-    
+
     /// Generate a new capability ID
     pub fn next_capability_id(&mut self) -> CapabilityId {
-        let id = CapabilityId(self.next_capability_id);
-        self.next_capability_id += 1;
-        id
+        self.registry.lock().next_capability_id()
     }
-    
-    /// Create a new capability channel
+
+    /// Create a new capability channel whose requests are validated against this system's registry
     pub fn create_channel(&mut self) -> CapabilityChannel {
-        let (channel, service) = CapabilityChannel::new();
+        let (channel, service) = CapabilityChannel::with_registry(Some(self.registry.clone()));
         self.channels.push(service);
         channel
     }
+
+    /// Register a root (non-delegated) capability with the system
+    pub fn register(&mut self, capability: Capability) {
+        self.registry.lock().register(capability);
+    }
+
+    /// Look up a live capability by id
+    pub fn get(&self, id: CapabilityId) -> Option<Capability> {
+        self.registry.lock().get(id).cloned()
+    }
+
+    /// Delegate a narrowed subset of `parent`'s permissions and resource to a new capability
+    pub fn delegate(
+        &mut self,
+        parent: CapabilityId,
+        subset: PermissionSet,
+        resource_narrowing: Option<ResourceHandle>,
+    ) -> Result<Capability, CapabilityError> {
+        self.registry.lock().delegate(parent, subset, resource_narrowing)
+    }
+
+    /// Revoke a capability and transitively invalidate every capability delegated from it
+    pub fn revoke(&mut self, id: CapabilityId) {
+        self.registry.lock().revoke(id);
+    }
+
+    /// Validate that a capability is still live (exists and unexpired)
+    pub fn validate(&self, id: CapabilityId) -> Result<(), CapabilityError> {
+        self.registry.lock().validate(id)
+    }
+
+    /// Drop every expired capability from the registry, cascading to their
+    /// delegated descendants. Called periodically by the kernel loop in
+    /// addition to the lazy, on-use check in `validate`.
+    pub fn sweep_expired(&mut self) -> usize {
+        self.registry.lock().sweep_expired()
+    }
 }
 
 /// Initialize capability system
 pub fn init() {
     crate::println!("Initializing capability system foundation...");
-    
+
     let capability_system = CapabilitySystem::new();
-    
+
     unsafe {
         CAPABILITY_SYSTEM = Some(capability_system);
     }
-    
+
     crate::println!("Capability system foundation initialized");
 }
 
@@ -6,7 +6,8 @@
 use alloc::string::ToString;
 use alloc::vec;
 use crate::capability::{
-    CapabilityRequest, FileSystemRequest, OpenMode, CapabilityChannel, CapabilityResponse
+    Capability, CapabilityRequest, FileSystemRequest, OpenMode, CapabilityChannel,
+    CapabilityResponse, CapabilitySystem, Permission, PermissionSet, ResourceHandle,
 };
 
 /// Example: File system access through capability channels
@@ -27,8 +28,20 @@ pub async fn example_file_access() -> Result<(), &'static str> {
     match channel.request(file_request).await {
         Ok(CapabilityResponse::Success(capability)) => {
             crate::println!("File access capability granted: {:?}", capability.id);
-            // Use the capability for subsequent file operations
-            Ok(())
+
+            // Use the granted capability for a subsequent file operation
+            // instead of minting a fresh one; this standalone channel has no
+            // registry, so the service dispatches without re-validating it
+            let list_request = CapabilityRequest::FileSystem(
+                FileSystemRequest::List { path: "/home/user/".to_string() }
+            );
+            match channel.request_with_capability(capability.id, list_request).await {
+                Ok(CapabilityResponse::Error(error)) => {
+                    crate::println!("Follow-up file operation denied: {}", error);
+                    Err("Follow-up file operation denied")
+                }
+                _ => Ok(()),
+            }
         }
         Ok(CapabilityResponse::Error(error)) => {
             crate::println!("File access denied: {}", error);
@@ -55,7 +68,19 @@ pub async fn example_network_access() -> Result<(), &'static str> {
     match channel.request(network_request).await {
         Ok(CapabilityResponse::Success(capability)) => {
             crate::println!("Network capability granted: {:?}", capability.id);
-            Ok(())
+
+            // Reuse the granted capability to send data, instead of minting
+            // a fresh one for every operation on the same connection
+            let send_request = CapabilityRequest::Network(
+                NetworkRequest::Send { data: vec![0u8; 0] }
+            );
+            match channel.request_with_capability(capability.id, send_request).await {
+                Ok(CapabilityResponse::Error(error)) => {
+                    crate::println!("Follow-up network operation denied: {}", error);
+                    Err("Follow-up network operation denied")
+                }
+                _ => Ok(()),
+            }
         }
         Ok(CapabilityResponse::Error(error)) => {
             crate::println!("Network access denied: {}", error);
@@ -82,7 +107,17 @@ pub async fn example_process_spawn() -> Result<(), &'static str> {
     match channel.request(process_request).await {
         Ok(CapabilityResponse::Success(capability)) => {
             crate::println!("Process spawn capability granted: {:?}", capability.id);
-            Ok(())
+
+            // Use the granted capability to signal the spawned process later,
+            // rather than requesting a fresh process capability for it
+            let signal_request = CapabilityRequest::Process(ProcessRequest::Signal { pid: 0, signal: 0 });
+            match channel.request_with_capability(capability.id, signal_request).await {
+                Ok(CapabilityResponse::Error(error)) => {
+                    crate::println!("Follow-up process operation denied: {}", error);
+                    Err("Follow-up process operation denied")
+                }
+                _ => Ok(()),
+            }
         }
         Ok(CapabilityResponse::Error(error)) => {
             crate::println!("Process spawn denied: {}", error);
@@ -105,14 +140,43 @@ pub fn demonstrate_capability_advantages() {
 
 /// Example of capability delegation (advanced feature)
 pub async fn example_capability_delegation() -> Result<(), &'static str> {
-    // In a full implementation, this would show how one process can
-    // grant a subset of its capabilities to another process
-    
+    // Shows how one process can grant a narrowed subset of its capabilities
+    // to another process, and how revoking the parent kills the delegated
+    // child as well.
+    let mut capability_system = CapabilitySystem::new();
+
+    let parent = Capability {
+        id: capability_system.next_capability_id(),
+        permissions: PermissionSet {
+            permissions: vec![Permission::Read, Permission::Write, Permission::Delegate],
+        },
+        resource: ResourceHandle::File("/home/user/".to_string()),
+        expires_at: None,
+        derived_from: None,
+    };
+    let parent_id = parent.id;
+    capability_system.register(parent);
+
     crate::println!("Capability delegation example:");
     crate::println!("- Parent process has file system capability for /home/user/");
-    crate::println!("- Parent delegates read-only capability for /home/user/documents/ to child");
+
+    let child = capability_system
+        .delegate(
+            parent_id,
+            PermissionSet { permissions: vec![Permission::Read] },
+            Some(ResourceHandle::File("/home/user/documents/".to_string())),
+        )
+        .map_err(|_| "delegation failed")?;
+
+    crate::println!(
+        "- Parent delegated read-only capability {:?} for /home/user/documents/ to child",
+        child.id
+    );
     crate::println!("- Child can only access documents, not the entire home directory");
+
+    capability_system.revoke(parent_id);
+    crate::println!("- Revoking the parent transitively invalidated the delegated child");
     crate::println!("- Delegation maintains the principle of least privilege");
-    
+
     Ok(())
 }
\ No newline at end of file
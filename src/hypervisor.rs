@@ -3,6 +3,7 @@
 /// This module implements the hypervisor-first design principle of RustOS,
 /// providing abstraction layers for different hypervisors (KVM, Xen, etc.)
 
+use core::arch::x86_64::__cpuid;
 use core::fmt;
 use alloc::vec::Vec;
 
@@ -14,6 +15,8 @@ pub enum HypervisorType {
     HyperV,
     VMware,
     Qemu,
+    /// Bare metal, or a hypervisor that doesn't advertise a recognized signature
+    Unknown,
 }
 
 /// Hypervisor interface trait
@@ -22,6 +25,28 @@ pub trait Hypervisor {
     fn get_type(&self) -> HypervisorType;
     fn get_memory_layout(&self) -> MemoryLayout;
     fn register_interrupt_handler(&self, vector: u8, handler: fn()) -> Result<(), HypervisorError>;
+
+    /// Enable confidential-computing memory encryption (SEV-SNP/TDX-style)
+    /// for the guest under `policy`. Returns `HypervisorError::UnsupportedFeature`
+    /// if the host doesn't advertise the capability.
+    fn enable_memory_encryption(&self, policy: EncryptionPolicy) -> Result<(), HypervisorError>;
+
+    /// Request the hypervisor encrypt (or re-encrypt under the current
+    /// policy) the physical range `[start, start + size)`
+    fn encrypt_region(&self, start: u64, size: u64);
+
+    /// Ask the hypervisor to reset the guest (conceptually a triple fault or
+    /// a dedicated reset vmexit), bypassing any further guest-side cleanup.
+    /// The control-event channel calls this only after quiescing devices,
+    /// as the last step of handling `ControlEvent::Reboot`.
+    fn request_reset(&self);
+}
+
+/// Confidential-computing policy for a memory region: which per-guest key
+/// the hypervisor should encrypt it under
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EncryptionPolicy {
+    pub key_id: u32,
 }
 
 /// Memory layout information from hypervisor
@@ -71,6 +96,23 @@ impl fmt::Display for HypervisorError {
 /// Global hypervisor instance
 static mut HYPERVISOR_INSTANCE: Option<&'static dyn Hypervisor> = None;
 
+/// Maximum CPUID leaf supported by the hypervisor's paravirtual interface
+/// (leaf `0x4000_0000`'s EAX), so later feature probing knows which leaves
+/// are safe to query without faulting. Zero on bare metal.
+static mut MAX_HYPERVISOR_LEAF: u32 = 0;
+
+/// Hypervisor vendor `detect_hypervisor()` last found, kept around so later
+/// feature probes (e.g. confidential-computing support) don't need a live
+/// `&dyn Hypervisor` instance to key off of
+static mut DETECTED_HYPERVISOR_TYPE: HypervisorType = HypervisorType::Unknown;
+
+/// CPUID leaf AMD hosts use to advertise SEV/SEV-ES/SEV-SNP support.
+/// Unlike the paravirtual interface's own feature leaves, this is queried
+/// directly since it's defined by the CPU vendor, not the hypervisor.
+const SEV_CPUID_LEAF: u32 = 0x8000_001f;
+/// EAX bit 4 of `SEV_CPUID_LEAF`: SEV-SNP is supported
+const SEV_SNP_SUPPORTED_BIT: u32 = 1 << 4;
+
 /// Initialize hypervisor integration
 pub fn init() {
     crate::println!("Initializing hypervisor integration...");
@@ -97,14 +139,75 @@ pub fn init() {
     crate::println!("Hypervisor integration initialized");
 }
 
-/// Detect the current hypervisor
+/// Detect the current hypervisor via CPUID
+///
+/// Leaf `0x1` ECX bit 31 is the "running under a hypervisor" flag. If set,
+/// leaf `0x4000_0000` gives the paravirtual interface's max supported leaf
+/// in EAX and a 12-byte vendor signature across EBX:ECX:EDX, which we match
+/// against the known vendor strings.
 fn detect_hypervisor() -> HypervisorType {
-    // TODO: Implement proper hypervisor detection using CPUID, etc.
-    // For now, default to KVM
-    HypervisorType::Kvm
+    let feature_info = unsafe { __cpuid(0x1) };
+    let hypervisor_present = feature_info.ecx & (1 << 31) != 0;
+    if !hypervisor_present {
+        return HypervisorType::Unknown;
+    }
+
+    let hypervisor_leaf = unsafe { __cpuid(0x4000_0000) };
+    unsafe {
+        MAX_HYPERVISOR_LEAF = hypervisor_leaf.eax;
+    }
+
+    let mut signature = [0u8; 12];
+    signature[0..4].copy_from_slice(&hypervisor_leaf.ebx.to_le_bytes());
+    signature[4..8].copy_from_slice(&hypervisor_leaf.ecx.to_le_bytes());
+    signature[8..12].copy_from_slice(&hypervisor_leaf.edx.to_le_bytes());
+
+    let detected = match &signature {
+        b"KVMKVMKVM\0\0\0" => HypervisorType::Kvm,
+        b"Microsoft Hv" => HypervisorType::HyperV,
+        b"XenVMMXenVMM" => HypervisorType::Xen,
+        b"VMwareVMware" => HypervisorType::VMware,
+        b"TCGTCGTCGTCG" => HypervisorType::Qemu,
+        _ => HypervisorType::Unknown,
+    };
+
+    unsafe {
+        DETECTED_HYPERVISOR_TYPE = detected;
+    }
+
+    detected
 }
 
 /// Get the current hypervisor instance
 pub fn get_hypervisor() -> Option<&'static dyn Hypervisor> {
     unsafe { HYPERVISOR_INSTANCE }
-}
\ No newline at end of file
+}
+
+/// Max CPUID leaf the detected hypervisor's paravirtual interface supports,
+/// or zero if we're on bare metal (or haven't detected one yet)
+pub fn max_hypervisor_leaf() -> u32 {
+    unsafe { MAX_HYPERVISOR_LEAF }
+}
+
+/// Hypervisor vendor `detect_hypervisor()` found at `init()` time
+pub fn detected_hypervisor_type() -> HypervisorType {
+    unsafe { DETECTED_HYPERVISOR_TYPE }
+}
+
+/// Probe whether the platform can provide guest-memory confidentiality.
+/// SEV-SNP rides under KVM (or bare metal, before a backend has attached),
+/// so both key off the CPU's own SEV-SNP feature bit; every other detected
+/// vendor is treated as unsupported until a TDX/SEV-aware backend lands.
+pub fn confidential_computing_policy(key_id: u32) -> Result<EncryptionPolicy, HypervisorError> {
+    match detected_hypervisor_type() {
+        HypervisorType::Kvm | HypervisorType::Unknown => {
+            let leaf = unsafe { __cpuid(SEV_CPUID_LEAF) };
+            if leaf.eax & SEV_SNP_SUPPORTED_BIT != 0 {
+                Ok(EncryptionPolicy { key_id })
+            } else {
+                Err(HypervisorError::UnsupportedFeature)
+            }
+        }
+        _ => Err(HypervisorError::UnsupportedFeature),
+    }
+}
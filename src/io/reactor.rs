@@ -0,0 +1,294 @@
+/// No-std, single-core async reactor
+///
+/// The I/O traits all return `Pin<Box<dyn Future>>`, but nothing polled them
+/// until now. This gives `IoSubsystem` a real (if minimal) scheduler: tasks
+/// are spawned onto a `Reactor`, polled whenever their waker fires, and the
+/// kernel halts (`hlt`) until the next device interrupt when nothing is
+/// runnable. Devices register interrupt interest through `register_interrupt`,
+/// which reserves one of a fixed set of `fn()` handler slots (the virtio
+/// drivers draw from the same pool, since `Hypervisor::register_interrupt_handler`
+/// takes a stateless function pointer) and hands back a `Token`; a `WaitContext`
+/// then lets any future register a waker against that token, crosvm-style.
+
+use core::future::Future;
+use core::pin::Pin;
+use core::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use core::task::{Context, Poll, RawWaker, RawWakerVTable, Waker};
+use alloc::collections::BTreeMap;
+use alloc::format;
+use alloc::boxed::Box;
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+
+use crate::hypervisor::Hypervisor;
+use super::IoError;
+
+/// Identifies one interrupt-driven readiness source a task can wait on
+pub(crate) type Token = u8;
+
+/// Fixed pool of interrupt handler slots reactor-registered devices draw from
+const MAX_REACTOR_SLOTS: usize = 8;
+
+/// Base interrupt vector reactor-managed devices are wired to, past the legacy PIC range
+const REACTOR_IRQ_BASE_VECTOR: u8 = 56;
+
+static REACTOR_IRQ_PENDING: [AtomicBool; MAX_REACTOR_SLOTS] = [
+    AtomicBool::new(false),
+    AtomicBool::new(false),
+    AtomicBool::new(false),
+    AtomicBool::new(false),
+    AtomicBool::new(false),
+    AtomicBool::new(false),
+    AtomicBool::new(false),
+    AtomicBool::new(false),
+];
+
+static NEXT_REACTOR_SLOT: AtomicUsize = AtomicUsize::new(0);
+
+fn reactor_irq_handler_0() {
+    REACTOR_IRQ_PENDING[0].store(true, Ordering::Release);
+}
+fn reactor_irq_handler_1() {
+    REACTOR_IRQ_PENDING[1].store(true, Ordering::Release);
+}
+fn reactor_irq_handler_2() {
+    REACTOR_IRQ_PENDING[2].store(true, Ordering::Release);
+}
+fn reactor_irq_handler_3() {
+    REACTOR_IRQ_PENDING[3].store(true, Ordering::Release);
+}
+fn reactor_irq_handler_4() {
+    REACTOR_IRQ_PENDING[4].store(true, Ordering::Release);
+}
+fn reactor_irq_handler_5() {
+    REACTOR_IRQ_PENDING[5].store(true, Ordering::Release);
+}
+fn reactor_irq_handler_6() {
+    REACTOR_IRQ_PENDING[6].store(true, Ordering::Release);
+}
+fn reactor_irq_handler_7() {
+    REACTOR_IRQ_PENDING[7].store(true, Ordering::Release);
+}
+
+const REACTOR_IRQ_HANDLERS: [fn(); MAX_REACTOR_SLOTS] = [
+    reactor_irq_handler_0,
+    reactor_irq_handler_1,
+    reactor_irq_handler_2,
+    reactor_irq_handler_3,
+    reactor_irq_handler_4,
+    reactor_irq_handler_5,
+    reactor_irq_handler_6,
+    reactor_irq_handler_7,
+];
+
+/// Reserve the next free reactor interrupt slot and wire it to the hypervisor,
+/// returning the `Token` devices use to wait on it through a `WaitContext`
+pub(crate) fn register_interrupt(hypervisor: &dyn Hypervisor) -> Result<Token, IoError> {
+    let slot = NEXT_REACTOR_SLOT.fetch_add(1, Ordering::SeqCst);
+    if slot >= MAX_REACTOR_SLOTS {
+        return Err(IoError::SystemError("out of reactor interrupt slots".into()));
+    }
+
+    let vector = REACTOR_IRQ_BASE_VECTOR + slot as u8;
+    hypervisor
+        .register_interrupt_handler(vector, REACTOR_IRQ_HANDLERS[slot])
+        .map_err(|e| IoError::SystemError(format!("interrupt registration failed: {}", e)))?;
+
+    Ok(slot as Token)
+}
+
+/// Take and clear `token`'s pending flag, returning whether it had fired.
+/// `pub(crate)` so `IoSubsystem` can poll a one-off token (e.g. a shutdown
+/// device's IRQ) without going through a `WaitContext`/future.
+pub(crate) fn take_pending(token: Token) -> bool {
+    REACTOR_IRQ_PENDING[token as usize].swap(false, Ordering::AcqRel)
+}
+
+/// Whether any registered interrupt has fired since it was last taken
+fn any_pending() -> bool {
+    REACTOR_IRQ_PENDING.iter().any(|pending| pending.load(Ordering::Acquire))
+}
+
+/// Registers interest in readiness events keyed by `Token` — crosvm's
+/// `WaitContext` pattern, adapted to bare-metal interrupt vectors instead of
+/// file descriptors. A future waiting on a token registers its waker here
+/// when it returns `Pending`; `wake_ready` wakes it once the token fires.
+#[derive(Default)]
+pub(crate) struct WaitContext {
+    wakers: BTreeMap<Token, Waker>,
+}
+
+impl WaitContext {
+    pub(crate) fn new() -> Self {
+        Self { wakers: BTreeMap::new() }
+    }
+
+    /// Register (or replace) the waker woken when `token`'s interrupt fires
+    pub(crate) fn add(&mut self, token: Token, waker: Waker) {
+        self.wakers.insert(token, waker);
+    }
+
+    pub(crate) fn remove(&mut self, token: Token) {
+        self.wakers.remove(&token);
+    }
+
+    /// Wake every registered task whose token has fired. The pending flag is
+    /// left set so the woken future's own poll can take it.
+    fn wake_ready(&mut self) {
+        for (&token, waker) in self.wakers.iter() {
+            if REACTOR_IRQ_PENDING[token as usize].load(Ordering::Acquire) {
+                waker.wake_by_ref();
+            }
+        }
+    }
+}
+
+/// A future that resolves the first time `token`'s interrupt fires.
+/// Existing virtio drivers still busy-wait on their virtqueues directly
+/// (an interim measure from before the reactor existed); new async code
+/// should await this instead of polling a queue in a spin loop.
+pub(crate) struct InterruptWait {
+    token: Token,
+    registered: bool,
+}
+
+impl InterruptWait {
+    pub(crate) fn new(token: Token) -> Self {
+        Self { token, registered: false }
+    }
+}
+
+impl Future for InterruptWait {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        if take_pending(self.token) {
+            return Poll::Ready(());
+        }
+
+        if let Some(io_subsystem) = crate::io::get_io_subsystem() {
+            io_subsystem.wait_context_mut().add(self.token, cx.waker().clone());
+            self.registered = true;
+        }
+
+        Poll::Pending
+    }
+}
+
+impl Drop for InterruptWait {
+    fn drop(&mut self) {
+        if self.registered {
+            if let Some(io_subsystem) = crate::io::get_io_subsystem() {
+                io_subsystem.wait_context_mut().remove(self.token);
+            }
+        }
+    }
+}
+
+/// A no-op `RawWaker` vtable backed by a shared "woken" flag: waking a task
+/// just flips the flag, and the executor's poll loop checks it each pass
+static TASK_WAKER_VTABLE: RawWakerVTable = RawWakerVTable::new(
+    task_waker_clone,
+    task_waker_wake,
+    task_waker_wake_by_ref,
+    task_waker_drop,
+);
+
+fn task_waker_clone(data: *const ()) -> RawWaker {
+    unsafe { Arc::increment_strong_count(data as *const AtomicBool) };
+    RawWaker::new(data, &TASK_WAKER_VTABLE)
+}
+
+fn task_waker_wake(data: *const ()) {
+    let flag = unsafe { Arc::from_raw(data as *const AtomicBool) };
+    flag.store(true, Ordering::Release);
+}
+
+fn task_waker_wake_by_ref(data: *const ()) {
+    let flag = unsafe { Arc::from_raw(data as *const AtomicBool) };
+    flag.store(true, Ordering::Release);
+    core::mem::forget(flag);
+}
+
+fn task_waker_drop(data: *const ()) {
+    unsafe { drop(Arc::from_raw(data as *const AtomicBool)) };
+}
+
+fn make_task_waker(woken: &Arc<AtomicBool>) -> Waker {
+    let data = Arc::into_raw(woken.clone()) as *const ();
+    unsafe { Waker::from_raw(RawWaker::new(data, &TASK_WAKER_VTABLE)) }
+}
+
+/// A spawned, not-yet-completed future plus the flag its waker sets
+struct Task {
+    future: Pin<Box<dyn Future<Output = ()> + Send>>,
+    woken: Arc<AtomicBool>,
+}
+
+/// A minimal single-core cooperative executor. There's no cross-task
+/// concurrency to schedule fairly, so readiness is tracked per task via a
+/// shared `woken` flag rather than a separate ready queue.
+#[derive(Default)]
+pub(crate) struct Reactor {
+    tasks: Vec<Task>,
+    /// Set while the I/O subsystem is quiesced (shutdown/reboot/pause), so
+    /// `run_once` stops polling until `unpark` is called
+    parked: bool,
+}
+
+impl Reactor {
+    pub(crate) fn new() -> Self {
+        Self { tasks: Vec::new(), parked: false }
+    }
+
+    /// Stop polling spawned tasks until `unpark`
+    pub(crate) fn park(&mut self) {
+        self.parked = true;
+    }
+
+    /// Resume polling spawned tasks
+    pub(crate) fn unpark(&mut self) {
+        self.parked = false;
+    }
+
+    /// Queue a future to run to completion, polled on the first `run` pass
+    pub(crate) fn spawn(&mut self, future: Pin<Box<dyn Future<Output = ()> + Send>>) {
+        self.tasks.push(Task { future, woken: Arc::new(AtomicBool::new(true)) });
+    }
+
+    /// Poll every task whose waker has fired since the last pass, dropping
+    /// completed ones. Returns whether any task was actually polled this
+    /// pass — i.e. whether progress was made, not whether the task list is
+    /// non-empty (a still-idle spawned task shouldn't keep the CPU spinning).
+    fn poll_ready(&mut self) -> bool {
+        let mut polled_any = false;
+        let mut i = 0;
+        while i < self.tasks.len() {
+            let ready = self.tasks[i].woken.swap(false, Ordering::AcqRel);
+            if ready {
+                polled_any = true;
+                let waker = make_task_waker(&self.tasks[i].woken);
+                let mut cx = Context::from_waker(&waker);
+                if self.tasks[i].future.as_mut().poll(&mut cx).is_ready() {
+                    self.tasks.swap_remove(i);
+                    continue;
+                }
+            }
+            i += 1;
+        }
+        polled_any
+    }
+
+    /// One reactor turn: poll whatever is ready, then, if nothing made
+    /// progress and no device interrupt is already pending, halt until the
+    /// next one arrives so the CPU isn't spinning with nothing to do.
+    pub(crate) fn run_once(&mut self, wait_context: &mut WaitContext) {
+        if self.parked {
+            return;
+        }
+        if !self.poll_ready() && !any_pending() {
+            x86_64::instructions::hlt();
+        }
+        wait_context.wake_ready();
+    }
+}
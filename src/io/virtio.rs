@@ -0,0 +1,895 @@
+/// virtio Device Transport and Drivers
+///
+/// Implements the virtio-mmio transport (virtqueue descriptor/avail/used
+/// rings, feature negotiation, and interrupt wiring through
+/// `Hypervisor::register_interrupt_handler`) plus `VirtioBlk`/`VirtioNet`
+/// drivers on top of it. Register layout and queue mechanics follow the
+/// VIRTIO 1.1 spec, sections 2 (virtqueues) and 4.2 (MMIO transport).
+
+use core::future::Future;
+use core::pin::Pin;
+use core::ptr;
+use core::task::{Context, Poll};
+use alloc::boxed::Box;
+use alloc::collections::BTreeMap;
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec;
+use alloc::vec::Vec;
+
+use crate::hypervisor::{Hypervisor, MemoryLayout, MemoryRegionType};
+use super::{
+    AsyncDevice, DeviceCapability, DeviceDescriptor, DeviceType, IoError, NetworkInterface,
+    NetworkPacket, NetworkProtocol, NetworkStack, StorageDevice,
+};
+
+/// Magic value ("virt" in little-endian ASCII) every virtio-mmio device exposes at offset 0x000
+const VIRTIO_MMIO_MAGIC: u32 = 0x7472_6976;
+
+const VIRTIO_DEVICE_ID_NET: u32 = 1;
+const VIRTIO_DEVICE_ID_BLOCK: u32 = 2;
+const VIRTIO_DEVICE_ID_BALLOON: u32 = 5;
+
+const VIRTIO_BALLOON_INFLATE_QUEUE: u16 = 0;
+const VIRTIO_BALLOON_DEFLATE_QUEUE: u16 = 1;
+
+/// Balloon PFNs are always expressed in 4 KiB units (VIRTIO 1.1 section 5.5.2),
+/// independent of the guest's actual page size
+const VIRTIO_BALLOON_PAGE_SIZE: usize = 4096;
+
+/// Fixed virtqueue size; small enough that the descriptor/avail/used rings
+/// stay cheap, large enough that a handful of in-flight requests don't
+/// stall waiting on a free descriptor
+const QUEUE_SIZE: usize = 16;
+
+const VIRTQ_DESC_F_NEXT: u16 = 1;
+const VIRTQ_DESC_F_WRITE: u16 = 2;
+
+/// virtio-mmio register offsets (VIRTIO 1.1 section 4.2.2)
+mod reg {
+    pub const MAGIC_VALUE: usize = 0x000;
+    pub const DEVICE_ID: usize = 0x008;
+    pub const DEVICE_FEATURES: usize = 0x010;
+    pub const DEVICE_FEATURES_SEL: usize = 0x014;
+    pub const DRIVER_FEATURES: usize = 0x020;
+    pub const DRIVER_FEATURES_SEL: usize = 0x024;
+    pub const QUEUE_SEL: usize = 0x030;
+    pub const QUEUE_NUM_MAX: usize = 0x034;
+    pub const QUEUE_NUM: usize = 0x038;
+    pub const QUEUE_READY: usize = 0x044;
+    pub const QUEUE_NOTIFY: usize = 0x050;
+    pub const INTERRUPT_STATUS: usize = 0x060;
+    pub const INTERRUPT_ACK: usize = 0x064;
+    pub const STATUS: usize = 0x070;
+    pub const QUEUE_DESC_LOW: usize = 0x080;
+    pub const QUEUE_DESC_HIGH: usize = 0x084;
+    pub const QUEUE_DRIVER_LOW: usize = 0x090;
+    pub const QUEUE_DRIVER_HIGH: usize = 0x094;
+    pub const QUEUE_DEVICE_LOW: usize = 0x0a0;
+    pub const QUEUE_DEVICE_HIGH: usize = 0x0a4;
+    pub const CONFIG: usize = 0x100;
+}
+
+/// Device status bits (VIRTIO 1.1 section 2.1)
+mod status {
+    pub const ACKNOWLEDGE: u32 = 1;
+    pub const DRIVER: u32 = 2;
+    pub const DRIVER_OK: u32 = 4;
+    pub const FEATURES_OK: u32 = 8;
+    pub const FAILED: u32 = 128;
+}
+
+/// A single entry in a virtqueue's descriptor table
+#[repr(C)]
+struct VirtqDesc {
+    addr: u64,
+    len: u32,
+    flags: u16,
+    next: u16,
+}
+
+/// The driver-owned "available" ring: buffers the device should process next
+#[repr(C)]
+struct VirtqAvail {
+    flags: u16,
+    idx: u16,
+    ring: [u16; QUEUE_SIZE],
+}
+
+/// A used-ring entry: which descriptor chain the device consumed, and how many bytes it wrote
+#[repr(C)]
+#[derive(Clone, Copy)]
+struct VirtqUsedElem {
+    id: u32,
+    len: u32,
+}
+
+/// The device-owned "used" ring: completed buffers the driver should reclaim
+#[repr(C)]
+struct VirtqUsed {
+    flags: u16,
+    idx: u16,
+    ring: [VirtqUsedElem; QUEUE_SIZE],
+}
+
+/// A negotiated virtqueue: descriptor table plus avail/used rings, and a
+/// free list of descriptors threaded through the table's own `next` fields
+struct Virtqueue {
+    index: u16,
+    desc: Box<[VirtqDesc; QUEUE_SIZE]>,
+    avail: Box<VirtqAvail>,
+    used: Box<VirtqUsed>,
+    free_head: u16,
+    num_free: u16,
+    last_used_idx: u16,
+}
+
+impl Virtqueue {
+    fn new(index: u16) -> Self {
+        let desc = Box::new(core::array::from_fn(|i| VirtqDesc {
+            addr: 0,
+            len: 0,
+            flags: 0,
+            next: if i + 1 < QUEUE_SIZE { (i + 1) as u16 } else { 0 },
+        }));
+        let avail = Box::new(VirtqAvail { flags: 0, idx: 0, ring: [0; QUEUE_SIZE] });
+        let used = Box::new(VirtqUsed {
+            flags: 0,
+            idx: 0,
+            ring: [VirtqUsedElem { id: 0, len: 0 }; QUEUE_SIZE],
+        });
+
+        Self {
+            index,
+            desc,
+            avail,
+            used,
+            free_head: 0,
+            num_free: QUEUE_SIZE as u16,
+            last_used_idx: 0,
+        }
+    }
+
+    fn desc_table_addr(&self) -> u64 {
+        self.desc.as_ptr() as u64
+    }
+
+    fn avail_addr(&self) -> u64 {
+        self.avail.as_ref() as *const VirtqAvail as u64
+    }
+
+    fn used_addr(&self) -> u64 {
+        self.used.as_ref() as *const VirtqUsed as u64
+    }
+
+    /// Build a descriptor chain for `buffers` (addr, len, device_writable)
+    /// and post it to the available ring, returning the chain's head index
+    fn add_chain(&mut self, buffers: &[(u64, u32, bool)]) -> Result<u16, IoError> {
+        if buffers.is_empty() || buffers.len() as u16 > self.num_free {
+            return Err(IoError::DeviceError("virtqueue full".to_string()));
+        }
+
+        let head = self.free_head;
+        let mut current = head;
+        for (i, &(addr, len, device_writable)) in buffers.iter().enumerate() {
+            let is_last = i + 1 == buffers.len();
+            let next = self.desc[current as usize].next;
+
+            let mut flags = 0u16;
+            if device_writable {
+                flags |= VIRTQ_DESC_F_WRITE;
+            }
+            if !is_last {
+                flags |= VIRTQ_DESC_F_NEXT;
+            }
+
+            self.desc[current as usize].addr = addr;
+            self.desc[current as usize].len = len;
+            self.desc[current as usize].flags = flags;
+
+            if is_last {
+                self.free_head = next;
+            } else {
+                current = next;
+            }
+        }
+        self.num_free -= buffers.len() as u16;
+
+        let slot = (self.avail.idx as usize) % QUEUE_SIZE;
+        self.avail.ring[slot] = head;
+        self.avail.idx = self.avail.idx.wrapping_add(1);
+
+        Ok(head)
+    }
+
+    /// Reclaim a completed chain's descriptors back onto the free list
+    fn free_chain(&mut self, head: u16) {
+        let mut current = head;
+        loop {
+            let flags = self.desc[current as usize].flags;
+            let next = self.desc[current as usize].next;
+            self.num_free += 1;
+            if flags & VIRTQ_DESC_F_NEXT == 0 {
+                self.desc[current as usize].next = self.free_head;
+                self.free_head = head;
+                return;
+            }
+            current = next;
+        }
+    }
+
+    /// Pop the next completed entry off the used ring, if any
+    fn pop_used(&mut self) -> Option<(u32, u32)> {
+        if self.last_used_idx == self.used.idx {
+            return None;
+        }
+        let slot = (self.last_used_idx as usize) % QUEUE_SIZE;
+        let elem = self.used.ring[slot];
+        self.last_used_idx = self.last_used_idx.wrapping_add(1);
+        Some((elem.id, elem.len))
+    }
+}
+
+/// A virtio-mmio transport instance, bound to one device's MMIO register page
+struct VirtioMmioTransport {
+    base: *mut u8,
+}
+
+// `base` is a fixed hardware MMIO address; the transport doesn't alias any
+// Rust-owned memory, so moving/sharing it across threads is sound as long
+// as accesses are still serialized by the driver (as they are here).
+unsafe impl Send for VirtioMmioTransport {}
+unsafe impl Sync for VirtioMmioTransport {}
+
+impl VirtioMmioTransport {
+    unsafe fn new(base: *mut u8) -> Self {
+        Self { base }
+    }
+
+    unsafe fn read32(&self, offset: usize) -> u32 {
+        ptr::read_volatile(self.base.add(offset) as *const u32)
+    }
+
+    unsafe fn write32(&self, offset: usize, value: u32) {
+        ptr::write_volatile(self.base.add(offset) as *mut u32, value)
+    }
+
+    unsafe fn read8(&self, offset: usize) -> u8 {
+        ptr::read_volatile(self.base.add(offset))
+    }
+
+    fn status(&self) -> u32 {
+        unsafe { self.read32(reg::STATUS) }
+    }
+
+    fn set_status(&self, value: u32) {
+        unsafe { self.write32(reg::STATUS, value) }
+    }
+
+    /// Reset the device to a known state (VIRTIO 1.1 section 3.1.1)
+    fn reset(&self) {
+        self.set_status(0);
+    }
+
+    /// Negotiate a feature subset: ACKNOWLEDGE -> DRIVER -> read device
+    /// features and keep only the ones both we and it support -> FEATURES_OK
+    /// -> verify the device accepted the negotiation
+    fn negotiate_features(&self, requested: u64) -> Result<u64, IoError> {
+        self.set_status(status::ACKNOWLEDGE);
+        self.set_status(self.status() | status::DRIVER);
+
+        unsafe { self.write32(reg::DEVICE_FEATURES_SEL, 0) };
+        let low = unsafe { self.read32(reg::DEVICE_FEATURES) } as u64;
+        unsafe { self.write32(reg::DEVICE_FEATURES_SEL, 1) };
+        let high = unsafe { self.read32(reg::DEVICE_FEATURES) } as u64;
+        let device_features = low | (high << 32);
+        let accepted = device_features & requested;
+
+        unsafe {
+            self.write32(reg::DRIVER_FEATURES_SEL, 0);
+            self.write32(reg::DRIVER_FEATURES, accepted as u32);
+            self.write32(reg::DRIVER_FEATURES_SEL, 1);
+            self.write32(reg::DRIVER_FEATURES, (accepted >> 32) as u32);
+        }
+
+        self.set_status(self.status() | status::FEATURES_OK);
+        if self.status() & status::FEATURES_OK == 0 {
+            self.set_status(status::FAILED);
+            return Err(IoError::DeviceError("device rejected feature negotiation".to_string()));
+        }
+
+        Ok(accepted)
+    }
+
+    fn set_driver_ok(&self) {
+        self.set_status(self.status() | status::DRIVER_OK);
+    }
+
+    /// Select, size, and register a fresh virtqueue with the device
+    fn setup_queue(&self, index: u16) -> Result<Virtqueue, IoError> {
+        unsafe { self.write32(reg::QUEUE_SEL, index as u32) };
+        let max = unsafe { self.read32(reg::QUEUE_NUM_MAX) };
+        if (max as usize) < QUEUE_SIZE {
+            return Err(IoError::DeviceError("virtio queue too small".to_string()));
+        }
+
+        let queue = Virtqueue::new(index);
+        let desc_addr = queue.desc_table_addr();
+        let avail_addr = queue.avail_addr();
+        let used_addr = queue.used_addr();
+
+        unsafe {
+            self.write32(reg::QUEUE_NUM, QUEUE_SIZE as u32);
+            self.write32(reg::QUEUE_DESC_LOW, desc_addr as u32);
+            self.write32(reg::QUEUE_DESC_HIGH, (desc_addr >> 32) as u32);
+            self.write32(reg::QUEUE_DRIVER_LOW, avail_addr as u32);
+            self.write32(reg::QUEUE_DRIVER_HIGH, (avail_addr >> 32) as u32);
+            self.write32(reg::QUEUE_DEVICE_LOW, used_addr as u32);
+            self.write32(reg::QUEUE_DEVICE_HIGH, (used_addr >> 32) as u32);
+            self.write32(reg::QUEUE_READY, 1);
+        }
+
+        Ok(queue)
+    }
+
+    fn notify_queue(&self, index: u16) {
+        unsafe { self.write32(reg::QUEUE_NOTIFY, index as u32) };
+    }
+
+    fn read_config_u32(&self, offset: usize) -> u32 {
+        unsafe { self.read32(reg::CONFIG + offset) }
+    }
+
+    fn read_config_u64(&self, offset: usize) -> u64 {
+        let low = self.read_config_u32(offset) as u64;
+        let high = self.read_config_u32(offset + 4) as u64;
+        low | (high << 32)
+    }
+}
+
+/// An already-resolved future, for device operations this driver completes synchronously.
+///
+/// Driver completion is still busy-waited inline (see `submit_and_wait` et
+/// al.) rather than driven by the reactor's waker slab; that migration is
+/// left for later since it touches every driver's internals, but the
+/// interrupt slot these devices register against (`super::reactor::register_interrupt`)
+/// is already the reactor's, so the plumbing is in place when it happens.
+struct ReadyFuture<T>(Option<T>);
+
+impl<T> Future for ReadyFuture<T> {
+    type Output = T;
+
+    fn poll(mut self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<T> {
+        Poll::Ready(self.0.take().expect("ReadyFuture polled after completion"))
+    }
+}
+
+fn ready<T: Send + 'static>(value: T) -> Pin<Box<dyn Future<Output = T> + Send>> {
+    Box::pin(ReadyFuture(Some(value)))
+}
+
+const VIRTIO_BLK_T_IN: u32 = 0;
+const VIRTIO_BLK_T_OUT: u32 = 1;
+const VIRTIO_BLK_T_DISCARD: u32 = 11;
+const VIRTIO_BLK_S_OK: u8 = 0;
+
+#[repr(C)]
+struct VirtioBlkReqHeader {
+    req_type: u32,
+    reserved: u32,
+    sector: u64,
+}
+
+#[repr(C)]
+struct VirtioBlkDiscardSegment {
+    sector: u64,
+    num_sectors: u32,
+    flags: u32,
+}
+
+/// virtio-blk device (VIRTIO 1.1 section 5.2): a single request virtqueue
+/// carrying sector-addressed read/write/discard requests
+struct VirtioBlk {
+    descriptor: DeviceDescriptor,
+    transport: VirtioMmioTransport,
+    queue: Virtqueue,
+    sector_size: u32,
+    total_sectors: u64,
+}
+
+impl VirtioBlk {
+    fn init(transport: VirtioMmioTransport, hypervisor: &dyn Hypervisor) -> Result<Self, IoError> {
+        transport.reset();
+        transport.negotiate_features(0)?;
+        let queue = transport.setup_queue(0)?;
+        super::reactor::register_interrupt(hypervisor)?;
+        transport.set_driver_ok();
+
+        let total_sectors = transport.read_config_u64(0x00);
+
+        Ok(Self {
+            descriptor: DeviceDescriptor {
+                device_id: 0,
+                device_type: DeviceType::BlockDevice,
+                name: "virtio-blk".to_string(),
+                vendor: "virtio".to_string(),
+                capabilities: vec![
+                    DeviceCapability::Read,
+                    DeviceCapability::Write,
+                    DeviceCapability::Flush,
+                    DeviceCapability::Interrupt,
+                ],
+            },
+            transport,
+            queue,
+            sector_size: 512,
+            total_sectors,
+        })
+    }
+
+    /// Submit a request descriptor chain and poll the used ring for its
+    /// completion. Completion is not driven by the reactor's wakers; this
+    /// busy-waits, which is safe since nothing else is waiting on this
+    /// queue's descriptors.
+    fn submit_and_wait(&mut self, chain: &[(u64, u32, bool)]) -> Result<u32, IoError> {
+        let head = self.queue.add_chain(chain)?;
+        self.transport.notify_queue(self.queue.index);
+
+        loop {
+            if let Some((id, len)) = self.queue.pop_used() {
+                self.queue.free_chain(id as u16);
+                if id as u16 == head {
+                    return Ok(len);
+                }
+            }
+            core::hint::spin_loop();
+        }
+    }
+
+    fn do_read(&mut self, sector: u64, buffer: &mut [u8]) -> Result<usize, IoError> {
+        let header = VirtioBlkReqHeader { req_type: VIRTIO_BLK_T_IN, reserved: 0, sector };
+        let mut resp_status = [VIRTIO_BLK_S_OK];
+
+        let chain = [
+            (&header as *const _ as u64, core::mem::size_of::<VirtioBlkReqHeader>() as u32, false),
+            (buffer.as_mut_ptr() as u64, buffer.len() as u32, true),
+            (resp_status.as_mut_ptr() as u64, 1, true),
+        ];
+
+        self.submit_and_wait(&chain)?;
+        if resp_status[0] != VIRTIO_BLK_S_OK {
+            return Err(IoError::DeviceError("virtio-blk read failed".to_string()));
+        }
+
+        Ok(buffer.len())
+    }
+
+    fn do_write(&mut self, sector: u64, data: &[u8]) -> Result<usize, IoError> {
+        let header = VirtioBlkReqHeader { req_type: VIRTIO_BLK_T_OUT, reserved: 0, sector };
+        let mut resp_status = [VIRTIO_BLK_S_OK];
+
+        let chain = [
+            (&header as *const _ as u64, core::mem::size_of::<VirtioBlkReqHeader>() as u32, false),
+            (data.as_ptr() as u64, data.len() as u32, false),
+            (resp_status.as_mut_ptr() as u64, 1, true),
+        ];
+
+        self.submit_and_wait(&chain)?;
+        if resp_status[0] != VIRTIO_BLK_S_OK {
+            return Err(IoError::DeviceError("virtio-blk write failed".to_string()));
+        }
+
+        Ok(data.len())
+    }
+
+    fn do_discard(&mut self, sector: u64, count: u32) -> Result<(), IoError> {
+        let header = VirtioBlkReqHeader { req_type: VIRTIO_BLK_T_DISCARD, reserved: 0, sector: 0 };
+        let segment = VirtioBlkDiscardSegment { sector, num_sectors: count, flags: 0 };
+        let mut resp_status = [VIRTIO_BLK_S_OK];
+
+        let chain = [
+            (&header as *const _ as u64, core::mem::size_of::<VirtioBlkReqHeader>() as u32, false),
+            (&segment as *const _ as u64, core::mem::size_of::<VirtioBlkDiscardSegment>() as u32, false),
+            (resp_status.as_mut_ptr() as u64, 1, true),
+        ];
+
+        self.submit_and_wait(&chain)?;
+        if resp_status[0] != VIRTIO_BLK_S_OK {
+            return Err(IoError::DeviceError("virtio-blk discard failed".to_string()));
+        }
+
+        Ok(())
+    }
+}
+
+impl AsyncDevice for VirtioBlk {
+    fn device_info(&self) -> &DeviceDescriptor {
+        &self.descriptor
+    }
+
+    fn read(&mut self, buffer: &mut [u8], offset: u64) -> Pin<Box<dyn Future<Output = Result<usize, IoError>> + Send>> {
+        if offset % self.sector_size as u64 != 0 || buffer.len() % self.sector_size as usize != 0 {
+            return ready(Err(IoError::InvalidInput));
+        }
+        let sector = offset / self.sector_size as u64;
+        let result = self.do_read(sector, buffer);
+        ready(result)
+    }
+
+    fn write(&mut self, data: &[u8], offset: u64) -> Pin<Box<dyn Future<Output = Result<usize, IoError>> + Send>> {
+        if offset % self.sector_size as u64 != 0 || data.len() % self.sector_size as usize != 0 {
+            return ready(Err(IoError::InvalidInput));
+        }
+        let sector = offset / self.sector_size as u64;
+        let result = self.do_write(sector, data);
+        ready(result)
+    }
+
+    fn flush(&mut self) -> Pin<Box<dyn Future<Output = Result<(), IoError>> + Send>> {
+        ready(Ok(()))
+    }
+
+    fn sync(&mut self) -> Pin<Box<dyn Future<Output = Result<(), IoError>> + Send>> {
+        ready(Ok(()))
+    }
+
+    fn queue_indices(&self) -> Vec<u16> {
+        vec![self.queue.index]
+    }
+}
+
+impl StorageDevice for VirtioBlk {
+    fn get_sector_size(&self) -> u32 {
+        self.sector_size
+    }
+
+    fn get_total_sectors(&self) -> u64 {
+        self.total_sectors
+    }
+
+    fn read_sectors(&mut self, sector: u64, count: u32, buffer: &mut [u8]) -> Pin<Box<dyn Future<Output = Result<usize, IoError>> + Send>> {
+        let expected = count as usize * self.sector_size as usize;
+        if buffer.len() < expected {
+            return ready(Err(IoError::InvalidInput));
+        }
+        let result = self.do_read(sector, &mut buffer[..expected]);
+        ready(result)
+    }
+
+    fn write_sectors(&mut self, sector: u64, count: u32, data: &[u8]) -> Pin<Box<dyn Future<Output = Result<usize, IoError>> + Send>> {
+        let expected = count as usize * self.sector_size as usize;
+        if data.len() < expected {
+            return ready(Err(IoError::InvalidInput));
+        }
+        let result = self.do_write(sector, &data[..expected]);
+        ready(result)
+    }
+
+    fn trim_sectors(&mut self, sector: u64, count: u32) -> Pin<Box<dyn Future<Output = Result<(), IoError>> + Send>> {
+        let result = self.do_discard(sector, count);
+        ready(result)
+    }
+}
+
+/// Legacy `struct virtio_net_hdr` layout (VIRTIO 1.1 section 5.1.6.1)
+#[repr(C)]
+struct VirtioNetHeader {
+    flags: u8,
+    gso_type: u8,
+    hdr_len: u16,
+    gso_size: u16,
+    csum_start: u16,
+    csum_offset: u16,
+    num_buffers: u16,
+}
+
+const VIRTIO_NET_HDR_LEN: usize = core::mem::size_of::<VirtioNetHeader>();
+const VIRTIO_NET_MAX_FRAME: usize = 1514;
+const VIRTIO_NET_RX_QUEUE: u16 = 0;
+const VIRTIO_NET_TX_QUEUE: u16 = 1;
+
+/// virtio-net device (VIRTIO 1.1 section 5.1): separate RX and TX virtqueues
+/// carrying raw Ethernet frames prefixed with a `virtio_net_hdr`
+struct VirtioNet {
+    interface: NetworkInterface,
+    transport: VirtioMmioTransport,
+    rx_queue: Virtqueue,
+    tx_queue: Virtqueue,
+    rx_buffers: BTreeMap<u16, Box<[u8]>>,
+    next_socket_id: u32,
+}
+
+impl VirtioNet {
+    fn init(transport: VirtioMmioTransport, hypervisor: &dyn Hypervisor) -> Result<Self, IoError> {
+        transport.reset();
+        transport.negotiate_features(0)?;
+        let rx_queue = transport.setup_queue(VIRTIO_NET_RX_QUEUE)?;
+        let tx_queue = transport.setup_queue(VIRTIO_NET_TX_QUEUE)?;
+        super::reactor::register_interrupt(hypervisor)?;
+        transport.set_driver_ok();
+
+        let mut mac_address = [0u8; 6];
+        for (i, byte) in mac_address.iter_mut().enumerate() {
+            *byte = unsafe { transport.read8(reg::CONFIG + i) };
+        }
+
+        let mut net = Self {
+            interface: NetworkInterface {
+                interface_id: 0,
+                name: "virtio-net0".to_string(),
+                mac_address,
+                mtu: 1500,
+                is_up: true,
+                ip_addresses: Vec::new(),
+            },
+            transport,
+            rx_queue,
+            tx_queue,
+            rx_buffers: BTreeMap::new(),
+            next_socket_id: 1,
+        };
+
+        for _ in 0..(QUEUE_SIZE / 2) {
+            net.post_rx_buffer();
+        }
+
+        Ok(net)
+    }
+
+    /// Hand the device a fresh buffer to receive its next frame into
+    fn post_rx_buffer(&mut self) {
+        let mut buffer = vec![0u8; VIRTIO_NET_HDR_LEN + VIRTIO_NET_MAX_FRAME].into_boxed_slice();
+        let addr = buffer.as_mut_ptr() as u64;
+        let len = buffer.len() as u32;
+
+        if let Ok(head) = self.rx_queue.add_chain(&[(addr, len, true)]) {
+            self.rx_buffers.insert(head, buffer);
+            self.transport.notify_queue(self.rx_queue.index);
+        }
+    }
+
+    fn receive_blocking(&mut self) -> Result<NetworkPacket, IoError> {
+        loop {
+            if let Some((id, len)) = self.rx_queue.pop_used() {
+                self.rx_queue.free_chain(id as u16);
+                if let Some(buffer) = self.rx_buffers.remove(&(id as u16)) {
+                    let payload_len = (len as usize).saturating_sub(VIRTIO_NET_HDR_LEN);
+                    let data = buffer[VIRTIO_NET_HDR_LEN..VIRTIO_NET_HDR_LEN + payload_len].to_vec();
+                    self.post_rx_buffer();
+                    return Ok(NetworkPacket {
+                        data,
+                        source: String::new(),
+                        destination: String::new(),
+                        protocol: NetworkProtocol::Ipv4,
+                        timestamp: crate::time::now_unix(),
+                    });
+                }
+            }
+            core::hint::spin_loop();
+        }
+    }
+
+    fn send_blocking(&mut self, packet: NetworkPacket) -> Result<(), IoError> {
+        let header = VirtioNetHeader {
+            flags: 0,
+            gso_type: 0,
+            hdr_len: 0,
+            gso_size: 0,
+            csum_start: 0,
+            csum_offset: 0,
+            num_buffers: 0,
+        };
+
+        let chain = [
+            (&header as *const _ as u64, VIRTIO_NET_HDR_LEN as u32, false),
+            (packet.data.as_ptr() as u64, packet.data.len() as u32, false),
+        ];
+
+        let head = self.tx_queue.add_chain(&chain)?;
+        self.transport.notify_queue(self.tx_queue.index);
+
+        loop {
+            if let Some((id, _len)) = self.tx_queue.pop_used() {
+                self.tx_queue.free_chain(id as u16);
+                if id as u16 == head {
+                    return Ok(());
+                }
+            }
+            core::hint::spin_loop();
+        }
+    }
+}
+
+impl NetworkStack for VirtioNet {
+    fn get_interfaces(&self) -> Vec<NetworkInterface> {
+        vec![self.interface.clone()]
+    }
+
+    fn send_packet(&mut self, packet: NetworkPacket) -> Pin<Box<dyn Future<Output = Result<(), IoError>> + Send>> {
+        let result = self.send_blocking(packet);
+        ready(result)
+    }
+
+    fn receive_packet(&mut self) -> Pin<Box<dyn Future<Output = Result<NetworkPacket, IoError>> + Send>> {
+        let result = self.receive_blocking();
+        ready(result)
+    }
+
+    fn create_socket(&mut self, _protocol: NetworkProtocol) -> Result<u32, IoError> {
+        let socket_id = self.next_socket_id;
+        self.next_socket_id += 1;
+        Ok(socket_id)
+    }
+
+    fn bind_socket(&mut self, _socket_id: u32, _address: String, _port: u16) -> Result<(), IoError> {
+        // TODO: virtio-net only hands us raw frames; binding needs a TCP/IP layer above it
+        Ok(())
+    }
+
+    fn connect_socket(&mut self, _socket_id: u32, _address: String, _port: u16) -> Pin<Box<dyn Future<Output = Result<(), IoError>> + Send>> {
+        ready(Err(IoError::SystemError("socket layer not implemented".to_string())))
+    }
+}
+
+/// virtio-balloon device (VIRTIO 1.1 section 5.5): lets the host ask the
+/// guest to shrink (inflate) or grow (deflate) its memory footprint by
+/// handing page frame numbers back and forth over a pair of virtqueues
+struct VirtioBalloon {
+    descriptor: DeviceDescriptor,
+    transport: VirtioMmioTransport,
+    inflate_queue: Virtqueue,
+    deflate_queue: Virtqueue,
+}
+
+impl VirtioBalloon {
+    fn init(transport: VirtioMmioTransport, hypervisor: &dyn Hypervisor) -> Result<Self, IoError> {
+        transport.reset();
+        transport.negotiate_features(0)?;
+        let inflate_queue = transport.setup_queue(VIRTIO_BALLOON_INFLATE_QUEUE)?;
+        let deflate_queue = transport.setup_queue(VIRTIO_BALLOON_DEFLATE_QUEUE)?;
+        super::reactor::register_interrupt(hypervisor)?;
+        transport.set_driver_ok();
+
+        Ok(Self {
+            descriptor: DeviceDescriptor {
+                device_id: 0,
+                device_type: DeviceType::MemoryDevice,
+                name: "virtio-balloon".to_string(),
+                vendor: "virtio".to_string(),
+                capabilities: vec![DeviceCapability::Interrupt],
+            },
+            transport,
+            inflate_queue,
+            deflate_queue,
+        })
+    }
+
+    /// Hand `pfns` (4 KiB page frame numbers) back to the host and deduct
+    /// their size from the memory manager's stats via `reclaim_pages`. The
+    /// heap allocator's free list is not changed.
+    pub(crate) fn inflate(&mut self, pfns: &[u32]) -> Result<(), IoError> {
+        let chain = [(pfns.as_ptr() as u64, (pfns.len() * core::mem::size_of::<u32>()) as u32, false)];
+        let head = self.inflate_queue.add_chain(&chain)?;
+        self.transport.notify_queue(self.inflate_queue.index);
+
+        loop {
+            if let Some((id, _len)) = self.inflate_queue.pop_used() {
+                self.inflate_queue.free_chain(id as u16);
+                if id as u16 == head {
+                    break;
+                }
+            }
+            core::hint::spin_loop();
+        }
+
+        if let Some(memory_manager) = crate::memory::get_memory_manager() {
+            memory_manager
+                .reclaim_pages(pfns.len() * VIRTIO_BALLOON_PAGE_SIZE)
+                .map_err(|e| IoError::DeviceError(format!("balloon inflate: {:?}", e)))?;
+        }
+
+        Ok(())
+    }
+
+    /// Take `pfns` (4 KiB page frame numbers) back from the host and add
+    /// their size back to the memory manager's stats via `release_pages`.
+    /// The heap allocator's free list is not changed.
+    pub(crate) fn deflate(&mut self, pfns: &[u32]) -> Result<(), IoError> {
+        let chain = [(pfns.as_ptr() as u64, (pfns.len() * core::mem::size_of::<u32>()) as u32, false)];
+        let head = self.deflate_queue.add_chain(&chain)?;
+        self.transport.notify_queue(self.deflate_queue.index);
+
+        loop {
+            if let Some((id, _len)) = self.deflate_queue.pop_used() {
+                self.deflate_queue.free_chain(id as u16);
+                if id as u16 == head {
+                    break;
+                }
+            }
+            core::hint::spin_loop();
+        }
+
+        if let Some(memory_manager) = crate::memory::get_memory_manager() {
+            memory_manager.release_pages(pfns.len() * VIRTIO_BALLOON_PAGE_SIZE);
+        }
+
+        Ok(())
+    }
+}
+
+impl AsyncDevice for VirtioBalloon {
+    fn device_info(&self) -> &DeviceDescriptor {
+        &self.descriptor
+    }
+
+    fn read(&mut self, _buffer: &mut [u8], _offset: u64) -> Pin<Box<dyn Future<Output = Result<usize, IoError>> + Send>> {
+        ready(Err(IoError::InvalidInput))
+    }
+
+    fn write(&mut self, _data: &[u8], _offset: u64) -> Pin<Box<dyn Future<Output = Result<usize, IoError>> + Send>> {
+        ready(Err(IoError::InvalidInput))
+    }
+
+    fn flush(&mut self) -> Pin<Box<dyn Future<Output = Result<(), IoError>> + Send>> {
+        ready(Ok(()))
+    }
+
+    fn sync(&mut self) -> Pin<Box<dyn Future<Output = Result<(), IoError>> + Send>> {
+        ready(Ok(()))
+    }
+
+    fn queue_indices(&self) -> Vec<u16> {
+        vec![self.inflate_queue.index, self.deflate_queue.index]
+    }
+}
+
+/// Walk the hypervisor-reported MMIO regions, probe each for a virtio magic
+/// value, and construct drivers for the block/network devices found
+pub(super) fn probe_virtio_devices(
+    layout: &MemoryLayout,
+    hypervisor: &dyn Hypervisor,
+) -> (Vec<Box<dyn AsyncDevice>>, Vec<Box<dyn NetworkStack>>) {
+    let mut async_devices: Vec<Box<dyn AsyncDevice>> = Vec::new();
+    let mut network_stacks: Vec<Box<dyn NetworkStack>> = Vec::new();
+
+    for region in &layout.reserved_regions {
+        if region.region_type != MemoryRegionType::Mmio {
+            continue;
+        }
+
+        let base = region.start as *mut u8;
+        let magic = unsafe { ptr::read_volatile(base.add(reg::MAGIC_VALUE) as *const u32) };
+        if magic != VIRTIO_MMIO_MAGIC {
+            continue;
+        }
+
+        let device_id = unsafe { ptr::read_volatile(base.add(reg::DEVICE_ID) as *const u32) };
+
+        match device_id {
+            VIRTIO_DEVICE_ID_BLOCK => {
+                let transport = unsafe { VirtioMmioTransport::new(base) };
+                match VirtioBlk::init(transport, hypervisor) {
+                    Ok(device) => async_devices.push(Box::new(device)),
+                    Err(e) => crate::println!("virtio-blk init failed at {:#x}: {:?}", region.start, e),
+                }
+            }
+            VIRTIO_DEVICE_ID_NET => {
+                let transport = unsafe { VirtioMmioTransport::new(base) };
+                match VirtioNet::init(transport, hypervisor) {
+                    Ok(device) => network_stacks.push(Box::new(device)),
+                    Err(e) => crate::println!("virtio-net init failed at {:#x}: {:?}", region.start, e),
+                }
+            }
+            VIRTIO_DEVICE_ID_BALLOON => {
+                let transport = unsafe { VirtioMmioTransport::new(base) };
+                match VirtioBalloon::init(transport, hypervisor) {
+                    Ok(device) => async_devices.push(Box::new(device)),
+                    Err(e) => crate::println!("virtio-balloon init failed at {:#x}: {:?}", region.start, e),
+                }
+            }
+            _ => {}
+        }
+    }
+
+    (async_devices, network_stacks)
+}
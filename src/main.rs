@@ -11,6 +11,8 @@ mod memory;
 mod process;
 mod capability;
 mod io;
+mod migration;
+mod time;
 
 use core::panic::PanicInfo;
 
@@ -18,13 +20,16 @@ use core::panic::PanicInfo;
 #[no_mangle]
 pub extern "C" fn _start() -> ! {
     println!("RustOS Kernel Starting...");
-    
+
     // Initialize hypervisor integration
     hypervisor::init();
-    
+
+    // Initialize kernel time subsystem
+    time::init();
+
     // Initialize memory management
     memory::init();
-    
+
     // Initialize capability system
     capability::init();
     
@@ -44,6 +49,20 @@ pub extern "C" fn _start() -> ! {
 fn kernel_main() -> ! {
     loop {
         // Kernel main loop - process capabilities, manage resources, etc.
+        time::tick();
+
+        if let Some(process_manager) = process::get_process_manager() {
+            process_manager.tick();
+        }
+
+        if let Some(capability_system) = capability::get_capability_system() {
+            capability_system.sweep_expired();
+        }
+
+        if let Some(io_subsystem) = io::get_io_subsystem() {
+            io_subsystem.run();
+        }
+
         x86_64::instructions::hlt();
     }
 }
@@ -9,24 +9,39 @@
 use core::alloc::{GlobalAlloc, Layout};
 use core::ptr::NonNull;
 use linked_list_allocator::LockedHeap;
+use alloc::collections::BTreeSet;
 use alloc::vec::Vec;
+use serde::{Deserialize, Serialize};
+
+use crate::hypervisor::EncryptionPolicy;
+use crate::migration::{MigrationError, Snapshot, SnapshotData};
 
 /// Global heap allocator
 #[global_allocator]
 static ALLOCATOR: LockedHeap = LockedHeap::empty();
 
 /// Memory management configuration
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MemoryConfig {
     pub heap_start: usize,
     pub heap_size: usize,
     pub page_size: usize,
     pub enable_memory_tagging: bool,
     pub enable_compression: bool,
+    pub hotplug_method: HotplugMethod,
+}
+
+/// Mechanism used to hotplug additional memory into a running guest
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum HotplugMethod {
+    /// Firmware exposes the new range as an ACPI0010 memory device
+    Acpi,
+    /// A virtio-mem device negotiates the new range directly with the host
+    VirtioMem,
 }
 
 /// Memory management statistics
-#[derive(Debug, Default)]
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
 pub struct MemoryStats {
     pub total_memory: usize,
     pub used_memory: usize,
@@ -43,10 +58,17 @@ pub enum MemoryError {
     PermissionDenied,
     AllocationFailed,
     DeallocationFailed,
+    /// An operation that requires a live hypervisor backend (e.g. confidential
+    /// computing memory encryption) was attempted with none attached
+    NoHypervisor,
+    /// A hotplug region's `start` doesn't immediately follow the heap's
+    /// current top, so `linked_list_allocator::Heap::extend` can't safely
+    /// fold it into the existing free list
+    NonContiguousRegion,
 }
 
 /// Memory region descriptor
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MemoryRegion {
     pub start: usize,
     pub size: usize,
@@ -55,7 +77,7 @@ pub struct MemoryRegion {
 }
 
 /// Memory permissions
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 pub struct MemoryPermissions {
     pub read: bool,
     pub write: bool,
@@ -63,12 +85,15 @@ pub struct MemoryPermissions {
 }
 
 /// Memory backing type
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum MemoryBacking {
     Physical,
     Swapped,
     Mapped,
     Shared,
+    /// Confidential-compute backed (SEV-SNP/TDX-style): encrypted by the
+    /// hypervisor under the guest key identified by `key_id`
+    Encrypted { key_id: u32 },
 }
 
 /// Memory manager
@@ -76,6 +101,14 @@ pub struct MemoryManager {
     config: MemoryConfig,
     stats: MemoryStats,
     regions: Vec<MemoryRegion>,
+    /// Page-aligned addresses touched by `map_region`/`hotplug_memory`, for
+    /// migration. `snapshot()` reports them and does not clear the set.
+    dirty_pages: BTreeSet<usize>,
+    /// Address immediately past the end of the heap `ALLOCATOR` currently
+    /// manages, so `hotplug_memory` can verify a new region is contiguous
+    /// with it before calling `extend`. Set by `init_heap`, advanced by
+    /// every successful `hotplug_memory` call.
+    heap_top: usize,
 }
 
 impl MemoryManager {
@@ -85,6 +118,22 @@ impl MemoryManager {
             config,
             stats: MemoryStats::default(),
             regions: Vec::new(),
+            dirty_pages: BTreeSet::new(),
+            heap_top: 0,
+        }
+    }
+
+    /// Mark every page overlapping `[addr, addr + size)` dirty. A snapshot
+    /// records the dirty page addresses, not the pages' contents.
+    fn mark_dirty(&mut self, addr: usize, size: usize) {
+        if size == 0 {
+            return;
+        }
+        let page_size = self.config.page_size;
+        let first_page = addr / page_size;
+        let last_page = (addr + size - 1) / page_size;
+        for page in first_page..=last_page {
+            self.dirty_pages.insert(page * page_size);
         }
     }
     
@@ -97,7 +146,8 @@ impl MemoryManager {
         
         self.stats.total_memory = self.config.heap_size;
         self.stats.free_memory = self.config.heap_size;
-        
+        self.heap_top = self.config.heap_start + self.config.heap_size;
+
         Ok(())
     }
     
@@ -107,11 +157,13 @@ impl MemoryManager {
         let layout = Layout::from_size_align(size, 8).map_err(|_| MemoryError::AllocationFailed)?;
         
         // For now, use the global allocator
-        // In a real implementation, this would track permissions and update stats
+        // In a real implementation, this would track permissions and update stats,
+        // and call self.mark_dirty(ptr.as_ptr() as usize, size) on success so
+        // incremental snapshots pick up the newly-touched pages
         self.stats.allocations += 1;
         self.stats.used_memory += size;
         self.stats.free_memory -= size;
-        
+
         Err(MemoryError::AllocationFailed) // Placeholder
     }
     
@@ -129,10 +181,77 @@ impl MemoryManager {
     pub fn get_stats(&self) -> &MemoryStats {
         &self.stats
     }
+
+    /// Extend the heap with an additional physical range the hypervisor has
+    /// handed the guest (cloud-hypervisor-style memory hotplug). The new
+    /// range must immediately follow the current heap's top, since
+    /// `linked_list_allocator`'s `extend` assumes a single contiguous heap.
+    pub fn hotplug_memory(&mut self, region: MemoryRegion) -> Result<(), MemoryError> {
+        if region.start != self.heap_top {
+            return Err(MemoryError::NonContiguousRegion);
+        }
+
+        match self.config.hotplug_method {
+            HotplugMethod::Acpi => crate::println!("Hotplugging {} bytes via ACPI memory device", region.size),
+            HotplugMethod::VirtioMem => crate::println!("Hotplugging {} bytes via virtio-mem", region.size),
+        }
+
+        unsafe {
+            ALLOCATOR.lock().extend(region.size);
+        }
+
+        self.stats.total_memory += region.size;
+        self.stats.free_memory += region.size;
+        self.heap_top += region.size;
+        self.mark_dirty(region.start, region.size);
+        self.regions.push(region);
+
+        Ok(())
+    }
+
+    /// Account for `bytes` worth of memory handed back to the host
+    /// (virtio-balloon inflate) by lowering `total_memory` and `free_memory`
+    /// in the stats. The heap allocator's own free list is not changed.
+    pub fn reclaim_pages(&mut self, bytes: usize) -> Result<(), MemoryError> {
+        if bytes > self.stats.free_memory {
+            return Err(MemoryError::OutOfMemory);
+        }
+
+        self.stats.free_memory -= bytes;
+        self.stats.total_memory -= bytes;
+        Ok(())
+    }
+
+    /// Account for `bytes` worth of memory taken back from the host
+    /// (virtio-balloon deflate) by raising `total_memory` and `free_memory`
+    /// in the stats. The heap allocator's own free list is not changed.
+    pub fn release_pages(&mut self, bytes: usize) {
+        self.stats.free_memory += bytes;
+        self.stats.total_memory += bytes;
+    }
     
-    /// Map a memory region
+    /// Map a memory region. If `region.backing` is `MemoryBacking::Encrypted`,
+    /// first ask the hypervisor to enable confidential-computing encryption
+    /// under that key and to encrypt the physical range before use.
     pub fn map_region(&mut self, region: MemoryRegion) -> Result<(), MemoryError> {
         // TODO: Implement memory mapping
+        if let MemoryBacking::Encrypted { key_id } = &region.backing {
+            let key_id = *key_id;
+            if !self.config.enable_memory_tagging {
+                return Err(MemoryError::PermissionDenied);
+            }
+
+            crate::hypervisor::confidential_computing_policy(key_id)
+                .map_err(|_| MemoryError::PermissionDenied)?;
+
+            let hypervisor = crate::hypervisor::get_hypervisor().ok_or(MemoryError::NoHypervisor)?;
+            hypervisor
+                .enable_memory_encryption(EncryptionPolicy { key_id })
+                .map_err(|_| MemoryError::PermissionDenied)?;
+            hypervisor.encrypt_region(region.start as u64, region.size as u64);
+        }
+
+        self.mark_dirty(region.start, region.size);
         self.regions.push(region);
         Ok(())
     }
@@ -145,6 +264,39 @@ impl MemoryManager {
     }
 }
 
+/// On-the-wire representation of a `MemoryManager` snapshot
+#[derive(Serialize, Deserialize)]
+struct MemoryManagerSnapshot {
+    config: MemoryConfig,
+    stats: MemoryStats,
+    regions: Vec<MemoryRegion>,
+    dirty_pages: Vec<usize>,
+}
+
+/// Format version for `MemoryManager` snapshots
+const MEMORY_SNAPSHOT_VERSION: u16 = 1;
+
+impl Snapshot for MemoryManager {
+    fn snapshot(&self) -> Result<SnapshotData, MigrationError> {
+        let snapshot = MemoryManagerSnapshot {
+            config: self.config.clone(),
+            stats: self.stats.clone(),
+            regions: self.regions.clone(),
+            dirty_pages: self.dirty_pages.iter().copied().collect(),
+        };
+        SnapshotData::encode(MEMORY_SNAPSHOT_VERSION, &snapshot)
+    }
+
+    fn restore(&mut self, data: SnapshotData) -> Result<(), MigrationError> {
+        let snapshot: MemoryManagerSnapshot = data.decode(MEMORY_SNAPSHOT_VERSION)?;
+        self.config = snapshot.config;
+        self.stats = snapshot.stats;
+        self.regions = snapshot.regions;
+        self.dirty_pages = snapshot.dirty_pages.into_iter().collect();
+        Ok(())
+    }
+}
+
 /// Global memory manager instance
 static mut MEMORY_MANAGER: Option<MemoryManager> = None;
 
@@ -171,6 +323,7 @@ pub fn init() {
         page_size: 4096,
         enable_memory_tagging: true,
         enable_compression: false,
+        hotplug_method: HotplugMethod::VirtioMem,
     };
     
     let mut memory_manager = MemoryManager::new(config);
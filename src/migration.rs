@@ -0,0 +1,103 @@
+/// Live Migration / Snapshot-Restore Subsystem
+///
+/// Lets a running kernel serialize its subsystems' state into a versioned,
+/// subsystem-keyed table of contents and later restore from one, mirroring
+/// the snapshot flow cloud-hypervisor uses for warm migration and
+/// suspend/resume of its memory and device managers.
+
+use alloc::collections::BTreeMap;
+use alloc::vec::Vec;
+use serde::{Deserialize, Serialize};
+
+/// Identifies which subsystem a `SnapshotData` blob in a `KernelSnapshot` belongs to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum SubsystemId {
+    Memory,
+    Devices,
+    Io,
+}
+
+/// Errors while snapshotting or restoring subsystem state
+#[derive(Debug)]
+pub enum MigrationError {
+    EncodingFailed,
+    DecodingFailed,
+    VersionMismatch { have: u16, want: u16 },
+    SubsystemNotFound(SubsystemId),
+}
+
+/// A versioned, encoded blob of one subsystem's state
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SnapshotData {
+    pub version: u16,
+    pub payload: Vec<u8>,
+}
+
+impl SnapshotData {
+    /// Encode a subsystem's state into a versioned blob
+    pub(crate) fn encode<T: Serialize>(version: u16, value: &T) -> Result<Self, MigrationError> {
+        let payload = postcard::to_allocvec(value).map_err(|_| MigrationError::EncodingFailed)?;
+        Ok(Self { version, payload })
+    }
+
+    /// Decode a blob, rejecting it outright if it wasn't produced by the expected version
+    pub(crate) fn decode<T: for<'de> Deserialize<'de>>(&self, expected_version: u16) -> Result<T, MigrationError> {
+        if self.version != expected_version {
+            return Err(MigrationError::VersionMismatch { have: self.version, want: expected_version });
+        }
+        postcard::from_bytes(&self.payload).map_err(|_| MigrationError::DecodingFailed)
+    }
+}
+
+/// Implemented by any subsystem that can serialize and restore its state for live migration
+pub trait Snapshot {
+    fn snapshot(&self) -> Result<SnapshotData, MigrationError>;
+    fn restore(&mut self, data: SnapshotData) -> Result<(), MigrationError>;
+}
+
+/// Current table-of-contents format version
+pub const TOC_VERSION: u16 = 1;
+
+/// A full snapshot: every subsystem's blob, keyed by id, plus the
+/// table-of-contents version so a restore can detect an incompatible snapshot
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KernelSnapshot {
+    pub toc_version: u16,
+    pub subsystems: BTreeMap<SubsystemId, SnapshotData>,
+}
+
+/// Snapshot every subsystem that supports live migration into one table of contents
+pub fn snapshot_all() -> Result<KernelSnapshot, MigrationError> {
+    let mut subsystems = BTreeMap::new();
+
+    if let Some(memory_manager) = crate::memory::get_memory_manager() {
+        subsystems.insert(SubsystemId::Memory, memory_manager.snapshot()?);
+    }
+
+    if let Some(io_subsystem) = crate::io::get_io_subsystem() {
+        subsystems.insert(SubsystemId::Io, io_subsystem.snapshot()?);
+    }
+
+    Ok(KernelSnapshot { toc_version: TOC_VERSION, subsystems })
+}
+
+/// Restore every subsystem present in `snapshot` back into the running kernel
+pub fn restore_all(snapshot: KernelSnapshot) -> Result<(), MigrationError> {
+    if snapshot.toc_version != TOC_VERSION {
+        return Err(MigrationError::VersionMismatch { have: snapshot.toc_version, want: TOC_VERSION });
+    }
+
+    if let Some(data) = snapshot.subsystems.get(&SubsystemId::Memory) {
+        let memory_manager = crate::memory::get_memory_manager()
+            .ok_or(MigrationError::SubsystemNotFound(SubsystemId::Memory))?;
+        memory_manager.restore(data.clone())?;
+    }
+
+    if let Some(data) = snapshot.subsystems.get(&SubsystemId::Io) {
+        let io_subsystem =
+            crate::io::get_io_subsystem().ok_or(MigrationError::SubsystemNotFound(SubsystemId::Io))?;
+        io_subsystem.restore(data.clone())?;
+    }
+
+    Ok(())
+}
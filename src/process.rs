@@ -53,6 +53,18 @@ pub struct ProcessControlBlock {
     pub priority: u8,
     pub creation_time: u64,
     pub cpu_time: u64,
+    /// Memory ceiling in bytes, from `ProcessCreateParams::memory_limit`
+    pub memory_limit: Option<usize>,
+    /// CPU budget in ticks, from `ProcessCreateParams::cpu_limit`
+    pub cpu_limit: Option<u64>,
+    /// Processes spawned by this one, in spawn order, for supervision fan-out
+    pub children: Vec<ProcessId>,
+    /// The parameters this process was created from, kept so a supervisor can respawn it
+    pub create_params: ProcessCreateParams,
+    /// If this process supervises its children, the restart policy to apply when one dies
+    pub supervision: Option<SupervisionPolicy>,
+    /// Tick timestamps of recent restarts performed by this supervisor, for intensity tracking
+    pub restart_history: VecDeque<u64>,
 }
 
 /// Thread control block
@@ -65,6 +77,10 @@ pub struct ThreadControlBlock {
     pub instruction_pointer: usize,
     pub priority: u8,
     pub cpu_time: u64,
+    /// Current MLFQ band (0 = highest priority, `MLFQ_BANDS - 1` = lowest)
+    pub band: usize,
+    /// Ticks remaining in the current band's quantum
+    pub remaining_quantum: u32,
 }
 
 /// Process creation parameters
@@ -86,6 +102,29 @@ pub struct ThreadCreateParams {
     pub priority: u8,
 }
 
+/// Erlang-style restart strategy a supervisor applies to its children
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RestartStrategy {
+    /// Restart only the child that died
+    OneForOne,
+    /// Restart every sibling, in spawn order
+    OneForAll,
+    /// Restart the child that died and every sibling spawned after it
+    RestForOne,
+}
+
+/// A supervisor's restart strategy and restart-intensity limit
+///
+/// If more than `max_restarts` restarts happen within `window_ticks`, the
+/// supervisor itself is considered unstable and is escalated (terminated,
+/// propagating the failure to its own parent).
+#[derive(Debug, Clone)]
+pub struct SupervisionPolicy {
+    pub strategy: RestartStrategy,
+    pub max_restarts: u32,
+    pub window_ticks: u64,
+}
+
 /// Process manager errors
 #[derive(Debug)]
 pub enum ProcessError {
@@ -97,69 +136,184 @@ pub enum ProcessError {
     SystemError(String),
 }
 
+/// Number of priority bands in the multi-level feedback queue
+pub const MLFQ_BANDS: usize = 8;
+
+/// Scheduler tuning knobs for the multi-level feedback queue
+///
+/// Band 0 is the most favored (shortest quantum, lowest latency) and
+/// `MLFQ_BANDS - 1` the least favored (longest quantum, for CPU-bound work).
+#[derive(Debug, Clone)]
+pub struct SchedulerConfig {
+    /// Quantum, in ticks, granted to a thread each time it is dispatched from a given band
+    pub band_quanta: [u32; MLFQ_BANDS],
+    /// Number of ticks between priority boosts, where every thread is flushed back to band 0
+    pub boost_interval_ticks: u64,
+}
+
+impl Default for SchedulerConfig {
+    fn default() -> Self {
+        Self {
+            // Each band gets roughly double the quantum of the one above it.
+            band_quanta: [2, 4, 8, 16, 32, 64, 128, 256],
+            boost_interval_ticks: 1000,
+        }
+    }
+}
+
+/// Map a thread's static `priority` byte to its starting MLFQ band
+///
+/// Lower priority values start closer to band 0 (more favored).
+fn priority_to_band(priority: u8) -> usize {
+    let band = (priority as usize * MLFQ_BANDS) / 256;
+    band.min(MLFQ_BANDS - 1)
+}
+
+/// What happens to a process that exceeds its `cpu_limit`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CpuLimitAction {
+    /// Move the process to `ProcessState::Blocked`; it can be resumed later
+    Block,
+    /// Terminate the process and all of its threads
+    Terminate,
+}
+
+/// cgroup-style resource accounting tuning knobs
+#[derive(Debug, Clone)]
+pub struct ResourceConfig {
+    /// What to do when a process's accumulated `cpu_time` reaches its `cpu_limit`
+    pub cpu_limit_action: CpuLimitAction,
+}
+
+impl Default for ResourceConfig {
+    fn default() -> Self {
+        Self { cpu_limit_action: CpuLimitAction::Block }
+    }
+}
+
+/// Current resource usage of a process versus its configured ceilings
+#[derive(Debug, Clone)]
+pub struct ResourceUsage {
+    pub memory_used: usize,
+    pub memory_limit: Option<usize>,
+    pub cpu_time: u64,
+    pub cpu_limit: Option<u64>,
+}
+
 /// Process manager
 pub struct ProcessManager {
     processes: Vec<ProcessControlBlock>,
     threads: Vec<ThreadControlBlock>,
     next_pid: AtomicU32,
     next_tid: AtomicU32,
-    ready_queue: VecDeque<ThreadId>,
+    /// Ready queues, one per MLFQ band, highest priority first
+    ready_queues: [VecDeque<ThreadId>; MLFQ_BANDS],
     current_thread: Option<ThreadId>,
+    scheduler_config: SchedulerConfig,
+    ticks_since_boost: u64,
+    resource_config: ResourceConfig,
 }
 
 impl ProcessManager {
-    /// Create a new process manager
+    /// Create a new process manager with the default scheduler and resource configuration
     pub fn new() -> Self {
+        Self::with_config(SchedulerConfig::default(), ResourceConfig::default())
+    }
+
+    /// Create a new process manager with a custom scheduler configuration
+    pub fn with_scheduler_config(scheduler_config: SchedulerConfig) -> Self {
+        Self::with_config(scheduler_config, ResourceConfig::default())
+    }
+
+    /// Create a new process manager with custom scheduler and resource configuration
+    pub fn with_config(scheduler_config: SchedulerConfig, resource_config: ResourceConfig) -> Self {
         Self {
             processes: Vec::new(),
             threads: Vec::new(),
             next_pid: AtomicU32::new(1),
             next_tid: AtomicU32::new(1),
-            ready_queue: VecDeque::new(),
+            ready_queues: core::array::from_fn(|_| VecDeque::new()),
             current_thread: None,
+            scheduler_config,
+            ticks_since_boost: 0,
+            resource_config,
         }
     }
-    
+
     /// Create a new process
+    ///
+    /// The new process's parent is whichever process owns the currently
+    /// running thread (i.e. the caller), mirroring `fork()`'s parent/child
+    /// relationship.
     pub fn create_process(&mut self, params: ProcessCreateParams) -> Result<ProcessId, ProcessError> {
+        let parent_pid = self.current_thread.and_then(|tid| self.get_thread(tid)).map(|t| t.pid);
+        self.create_process_with_parent(params, parent_pid)
+    }
+
+    /// Create a new process with an explicit parent, used both by
+    /// `create_process` and by supervisor-driven respawns
+    fn create_process_with_parent(
+        &mut self,
+        params: ProcessCreateParams,
+        parent_pid: Option<ProcessId>,
+    ) -> Result<ProcessId, ProcessError> {
         let pid = ProcessId(self.next_pid.fetch_add(1, Ordering::SeqCst));
-        
+
         // TODO: Load program from file system
         // TODO: Set up initial memory regions
         // TODO: Validate capabilities
-        
+
         let pcb = ProcessControlBlock {
             pid,
-            parent_pid: None, // TODO: Get current process PID
+            parent_pid,
             state: ProcessState::Created,
             capabilities: Vec::new(), // TODO: Convert CapabilityIds to Capabilities
             memory_regions: Vec::new(),
             threads: Vec::new(),
             priority: 128, // Default priority
-            creation_time: 0, // TODO: Get current time
+            creation_time: crate::time::now_unix(),
             cpu_time: 0,
+            memory_limit: params.memory_limit,
+            cpu_limit: params.cpu_limit,
+            children: Vec::new(),
+            create_params: params,
+            supervision: None,
+            restart_history: VecDeque::new(),
         };
-        
+
         self.processes.push(pcb);
-        
+
+        if let Some(parent_pid) = parent_pid {
+            if let Some(parent) = self.processes.iter_mut().find(|p| p.pid == parent_pid) {
+                parent.children.push(pid);
+            }
+        }
+
         // Create initial thread
         let thread_params = ThreadCreateParams {
             entry_point: 0x400000, // TODO: Get entry point from executable
             stack_size: 0x100000,  // 1MB stack
             priority: 128,
         };
-        
+
         let tid = self.create_thread(pid, thread_params)?;
-        
+
         // Add thread to process
         if let Some(process) = self.processes.iter_mut().find(|p| p.pid == pid) {
             process.threads.push(tid);
             process.state = ProcessState::Ready;
         }
-        
+
         Ok(pid)
     }
-    
+
+    /// Register a process as a supervisor, applying `policy` to restart its children when they die
+    pub fn set_supervision_policy(&mut self, pid: ProcessId, policy: SupervisionPolicy) -> Result<(), ProcessError> {
+        let process = self.processes.iter_mut().find(|p| p.pid == pid).ok_or(ProcessError::ProcessNotFound)?;
+        process.supervision = Some(policy);
+        Ok(())
+    }
+
     /// Create a new thread
     pub fn create_thread(&mut self, pid: ProcessId, params: ThreadCreateParams) -> Result<ThreadId, ProcessError> {
         // Verify process exists
@@ -172,6 +326,7 @@ impl ProcessManager {
         // TODO: Allocate stack
         // TODO: Set up initial register state
         
+        let band = priority_to_band(params.priority);
         let tcb = ThreadControlBlock {
             tid,
             pid,
@@ -180,14 +335,16 @@ impl ProcessManager {
             instruction_pointer: params.entry_point,
             priority: params.priority,
             cpu_time: 0,
+            band,
+            remaining_quantum: self.scheduler_config.band_quanta[band],
         };
-        
+
         self.threads.push(tcb);
-        self.ready_queue.push_back(tid);
-        
+        self.ready_queues[band].push_back(tid);
+
         Ok(tid)
     }
-    
+
     /// Terminate a process
     pub fn terminate_process(&mut self, pid: ProcessId) -> Result<(), ProcessError> {
         // Find and terminate all threads in the process
@@ -196,11 +353,11 @@ impl ProcessManager {
             .filter(|t| t.pid == pid)
             .map(|t| t.tid)
             .collect();
-        
+
         for tid in thread_ids {
             self.terminate_thread(tid)?;
         }
-        
+
         // Mark process as terminated
         if let Some(process) = self.processes.iter_mut().find(|p| p.pid == pid) {
             process.state = ProcessState::Terminated;
@@ -208,10 +365,83 @@ impl ProcessManager {
         } else {
             return Err(ProcessError::ProcessNotFound);
         }
-        
+
+        self.handle_supervised_termination(pid);
+
         Ok(())
     }
-    
+
+    /// If the terminated process has a supervising parent, apply the
+    /// parent's restart strategy; escalate to the parent itself if its
+    /// restart intensity has been exceeded
+    fn handle_supervised_termination(&mut self, pid: ProcessId) {
+        let parent_pid = match self.get_process(pid).and_then(|p| p.parent_pid) {
+            Some(parent_pid) => parent_pid,
+            None => return,
+        };
+
+        let policy = match self.get_process(parent_pid).and_then(|p| p.supervision.clone()) {
+            Some(policy) => policy,
+            None => return,
+        };
+
+        let now = crate::time::monotonic_ticks();
+        let intensity_exceeded = if let Some(parent) = self.processes.iter_mut().find(|p| p.pid == parent_pid) {
+            parent.restart_history.retain(|&t| now.saturating_sub(t) <= policy.window_ticks);
+            parent.restart_history.push_back(now);
+            parent.restart_history.len() as u32 > policy.max_restarts
+        } else {
+            false
+        };
+
+        if intensity_exceeded {
+            // The supervisor itself is unstable; escalate the failure upward.
+            let _ = self.terminate_process(parent_pid);
+            return;
+        }
+
+        let siblings = self.get_process(parent_pid).map(|p| p.children.clone()).unwrap_or_default();
+        let to_restart: Vec<ProcessId> = match policy.strategy {
+            RestartStrategy::OneForOne => alloc::vec![pid],
+            RestartStrategy::OneForAll => siblings,
+            RestartStrategy::RestForOne => match siblings.iter().position(|&sibling| sibling == pid) {
+                Some(index) => siblings[index..].to_vec(),
+                None => alloc::vec![pid],
+            },
+        };
+
+        for child_pid in to_restart {
+            self.respawn_child(parent_pid, child_pid);
+        }
+    }
+
+    /// Replace a dead child with a freshly spawned process created from its
+    /// original `ProcessCreateParams`, keeping it registered under the same supervisor
+    fn respawn_child(&mut self, parent_pid: ProcessId, old_pid: ProcessId) {
+        let create_params = match self.get_process(old_pid) {
+            Some(process) => process.create_params.clone(),
+            None => return,
+        };
+
+        let removed_tids: Vec<ThreadId> = self.threads.iter().filter(|t| t.pid == old_pid).map(|t| t.tid).collect();
+        self.threads.retain(|t| t.pid != old_pid);
+        for queue in self.ready_queues.iter_mut() {
+            queue.retain(|tid| !removed_tids.contains(tid));
+        }
+        if self.current_thread.map_or(false, |tid| removed_tids.contains(&tid)) {
+            self.current_thread = None;
+            self.schedule_next();
+        }
+        self.processes.retain(|p| p.pid != old_pid);
+        if let Some(parent) = self.processes.iter_mut().find(|p| p.pid == parent_pid) {
+            parent.children.retain(|&child| child != old_pid);
+        }
+
+        if let Ok(new_pid) = self.create_process_with_parent(create_params, Some(parent_pid)) {
+            crate::println!("Supervisor {:?} restarted child {:?} as {:?}", parent_pid, old_pid, new_pid);
+        }
+    }
+
     /// Terminate a thread
     pub fn terminate_thread(&mut self, tid: ThreadId) -> Result<(), ProcessError> {
         if let Some(thread) = self.threads.iter_mut().find(|t| t.tid == tid) {
@@ -220,62 +450,284 @@ impl ProcessManager {
         } else {
             return Err(ProcessError::ThreadNotFound);
         }
-        
-        // Remove from ready queue
-        self.ready_queue.retain(|&t| t != tid);
-        
+
+        // Remove from every band's ready queue
+        for queue in self.ready_queues.iter_mut() {
+            queue.retain(|&t| t != tid);
+        }
+
         // If this was the current thread, schedule next
         if self.current_thread == Some(tid) {
             self.current_thread = None;
             self.schedule_next();
         }
-        
+
         Ok(())
     }
-    
+
     /// Get process by PID
     pub fn get_process(&self, pid: ProcessId) -> Option<&ProcessControlBlock> {
         self.processes.iter().find(|p| p.pid == pid)
     }
-    
+
     /// Get thread by TID
     pub fn get_thread(&self, tid: ThreadId) -> Option<&ThreadControlBlock> {
         self.threads.iter().find(|t| t.tid == tid)
     }
-    
+
     /// Schedule next thread to run
+    ///
+    /// Picks from the highest non-empty MLFQ band and grants it a fresh
+    /// quantum for that band.
     pub fn schedule_next(&mut self) -> Option<ThreadId> {
-        // Simple round-robin scheduler
-        if let Some(next_tid) = self.ready_queue.pop_front() {
-            // Verify thread is still ready
-            if let Some(thread) = self.threads.iter().find(|t| t.tid == next_tid) {
-                if thread.state == ThreadState::Ready {
-                    self.current_thread = Some(next_tid);
-                    // Put thread back at end of queue for round-robin
-                    self.ready_queue.push_back(next_tid);
-                    return Some(next_tid);
+        for band in 0..MLFQ_BANDS {
+            while let Some(next_tid) = self.ready_queues[band].pop_front() {
+                // Verify thread is still ready
+                if let Some(thread) = self.threads.iter_mut().find(|t| t.tid == next_tid) {
+                    if thread.state == ThreadState::Ready {
+                        thread.band = band;
+                        thread.remaining_quantum = self.scheduler_config.band_quanta[band];
+                        self.current_thread = Some(next_tid);
+                        return Some(next_tid);
+                    }
                 }
+                // Stale or no-longer-ready entry; drop it and keep looking.
             }
         }
-        
+
         None
     }
-    
+
     /// Get current running thread
     pub fn get_current_thread(&self) -> Option<ThreadId> {
         self.current_thread
     }
-    
+
     /// Yield current thread
+    ///
+    /// A voluntary yield before the quantum is exhausted keeps the thread in
+    /// its current band.
     pub fn yield_thread(&mut self) {
         if let Some(current) = self.current_thread {
-            // Move current thread to back of ready queue
-            self.ready_queue.push_back(current);
+            if let Some(thread) = self.threads.iter().find(|t| t.tid == current) {
+                let band = thread.band;
+                self.ready_queues[band].push_back(current);
+            }
+            self.current_thread = None;
         }
-        
+
         // Schedule next thread
         self.schedule_next();
     }
+
+    /// Advance the scheduler by one timer tick
+    ///
+    /// Charges the running thread's quantum and demotes it a band if the
+    /// quantum is exhausted. Periodically boosts every thread back to band 0
+    /// to prevent starvation of lower-priority work.
+    pub fn tick(&mut self) {
+        if let Some(current) = self.current_thread {
+            let mut quantum_exhausted = false;
+            let mut band = 0;
+            let mut pid = None;
+
+            if let Some(thread) = self.threads.iter_mut().find(|t| t.tid == current) {
+                thread.cpu_time += 1;
+                if thread.remaining_quantum > 0 {
+                    thread.remaining_quantum -= 1;
+                }
+                quantum_exhausted = thread.remaining_quantum == 0;
+                band = thread.band;
+                pid = Some(thread.pid);
+            }
+
+            if let Some(pid) = pid {
+                self.charge_process_cpu_time(pid);
+            }
+
+            // charge_process_cpu_time can block `current` via enforce_cpu_limit,
+            // which already pulls it off every ready queue, clears
+            // current_thread, and schedules a replacement. If that happened,
+            // the quantum-exhaustion decision below is stale and must not
+            // run — acting on it would resurrect a blocked thread into a
+            // ready queue and discard whatever schedule_next() just picked.
+            if quantum_exhausted && self.current_thread == Some(current) {
+                let demoted_band = (band + 1).min(MLFQ_BANDS - 1);
+                if let Some(thread) = self.threads.iter_mut().find(|t| t.tid == current) {
+                    thread.band = demoted_band;
+                    thread.remaining_quantum = self.scheduler_config.band_quanta[demoted_band];
+                }
+                self.ready_queues[demoted_band].push_back(current);
+                self.current_thread = None;
+                self.schedule_next();
+            }
+        }
+
+        self.ticks_since_boost += 1;
+        if self.ticks_since_boost >= self.scheduler_config.boost_interval_ticks {
+            self.priority_boost();
+            self.ticks_since_boost = 0;
+        }
+    }
+
+    /// Accumulate one tick of CPU time onto a process's PCB and enforce its CPU budget
+    fn charge_process_cpu_time(&mut self, pid: ProcessId) {
+        let exceeded = if let Some(process) = self.processes.iter_mut().find(|p| p.pid == pid) {
+            process.cpu_time += 1;
+            process.cpu_limit.map_or(false, |limit| process.cpu_time >= limit)
+        } else {
+            false
+        };
+
+        if exceeded {
+            self.enforce_cpu_limit(pid);
+        }
+    }
+
+    /// Apply the configured `CpuLimitAction` to a process that exceeded its CPU budget
+    fn enforce_cpu_limit(&mut self, pid: ProcessId) {
+        match self.resource_config.cpu_limit_action {
+            CpuLimitAction::Block => {
+                let pid_threads: Vec<ThreadId> = self.threads
+                    .iter()
+                    .filter(|t| t.pid == pid)
+                    .map(|t| t.tid)
+                    .collect();
+
+                if let Some(process) = self.processes.iter_mut().find(|p| p.pid == pid) {
+                    process.state = ProcessState::Blocked;
+                }
+                for thread in self.threads.iter_mut().filter(|t| t.pid == pid) {
+                    thread.state = ThreadState::Blocked;
+                }
+                for queue in self.ready_queues.iter_mut() {
+                    queue.retain(|tid| !pid_threads.contains(tid));
+                }
+                if self.current_thread.map_or(false, |tid| pid_threads.contains(&tid)) {
+                    self.current_thread = None;
+                    self.schedule_next();
+                }
+            }
+            CpuLimitAction::Terminate => {
+                let _ = self.terminate_process(pid);
+            }
+        }
+    }
+
+    /// Allocate a memory region to a process, enforcing its `memory_limit` ceiling
+    pub fn allocate_memory(&mut self, pid: ProcessId, region: MemoryRegion) -> Result<(), ProcessError> {
+        let process = self.processes.iter_mut().find(|p| p.pid == pid).ok_or(ProcessError::ProcessNotFound)?;
+
+        if let Some(limit) = process.memory_limit {
+            let used: usize = process.memory_regions.iter().map(|r| r.size).sum();
+            if used + region.size > limit {
+                return Err(ProcessError::ResourceExhausted);
+            }
+        }
+
+        process.memory_regions.push(region);
+        Ok(())
+    }
+
+    /// Report a process's current resource usage versus its configured ceilings
+    pub fn resource_usage(&self, pid: ProcessId) -> Result<ResourceUsage, ProcessError> {
+        let process = self.get_process(pid).ok_or(ProcessError::ProcessNotFound)?;
+        let memory_used = process.memory_regions.iter().map(|r| r.size).sum();
+
+        Ok(ResourceUsage {
+            memory_used,
+            memory_limit: process.memory_limit,
+            cpu_time: process.cpu_time,
+            cpu_limit: process.cpu_limit,
+        })
+    }
+
+    /// Flush every ready thread back to band 0 so starved, low-priority
+    /// threads eventually get scheduled
+    fn priority_boost(&mut self) {
+        for band in 1..MLFQ_BANDS {
+            while let Some(tid) = self.ready_queues[band].pop_front() {
+                self.ready_queues[0].push_back(tid);
+            }
+        }
+
+        for thread in self.threads.iter_mut() {
+            if thread.state == ThreadState::Ready || Some(thread.tid) == self.current_thread {
+                thread.band = 0;
+                thread.remaining_quantum = self.scheduler_config.band_quanta[0];
+            }
+        }
+    }
+}
+
+#[test_case]
+fn tick_demotes_thread_after_quantum_exhaustion() {
+    let mut manager = ProcessManager::with_scheduler_config(SchedulerConfig {
+        band_quanta: [1, 2, 4, 8, 16, 32, 64, 128],
+        boost_interval_ticks: 1000,
+    });
+
+    manager
+        .create_process(ProcessCreateParams {
+            program_path: String::from("/bin/test"),
+            arguments: Vec::new(),
+            environment: Vec::new(),
+            capabilities: Vec::new(),
+            memory_limit: None,
+            cpu_limit: None,
+        })
+        .expect("create_process");
+
+    manager.schedule_next();
+    let tid = manager.get_current_thread().expect("a thread should be scheduled");
+    // The default thread priority (128) maps to band 4, whose quantum is 16 ticks.
+    assert_eq!(manager.get_thread(tid).unwrap().band, 4);
+
+    // Sixteen ticks exhaust band 4's quantum and demote the thread to
+    // band 5, rescheduling it (the only runnable thread) straight back
+    // to current with band 5's fresh quantum.
+    for _ in 0..16 {
+        manager.tick();
+    }
+
+    let thread = manager.get_thread(tid).unwrap();
+    assert_eq!(thread.band, 5);
+    assert_eq!(thread.remaining_quantum, 32);
+    assert_eq!(manager.get_current_thread(), Some(tid));
+}
+
+#[test_case]
+fn one_for_one_restart_respawns_only_the_dead_child() {
+    let params = || ProcessCreateParams {
+        program_path: String::from("/bin/test"),
+        arguments: Vec::new(),
+        environment: Vec::new(),
+        capabilities: Vec::new(),
+        memory_limit: None,
+        cpu_limit: None,
+    };
+
+    let mut manager = ProcessManager::new();
+    let parent_pid = manager.create_process(params()).expect("create parent");
+    manager
+        .set_supervision_policy(parent_pid, SupervisionPolicy {
+            strategy: RestartStrategy::OneForOne,
+            max_restarts: 10,
+            window_ticks: 1000,
+        })
+        .expect("set_supervision_policy");
+
+    let child_a = manager.create_process_with_parent(params(), Some(parent_pid)).expect("create child a");
+    let child_b = manager.create_process_with_parent(params(), Some(parent_pid)).expect("create child b");
+
+    manager.terminate_process(child_a).expect("terminate child a");
+
+    // OneForOne only restarts the child that died: child_b's pid survives
+    // untouched, and child_a's pid is replaced by a freshly spawned sibling.
+    let siblings = manager.get_process(parent_pid).unwrap().children.clone();
+    assert_eq!(siblings.len(), 2);
+    assert!(siblings.contains(&child_b));
+    assert!(!siblings.contains(&child_a));
 }
 
 /// Global process manager instance
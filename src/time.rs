@@ -0,0 +1,40 @@
+/// Kernel Time Subsystem
+///
+/// Provides the monotonic clock the rest of the kernel stamps timestamps
+/// from: a tick counter advanced once per timer interrupt, plus a coarse
+/// Unix-time view derived from it. Until the hypervisor module wires up a
+/// real RTC/TSC-calibrated source, `now_unix()` is boot time plus elapsed
+/// ticks.
+
+use core::sync::atomic::{AtomicU64, Ordering};
+
+static MONOTONIC_TICKS: AtomicU64 = AtomicU64::new(0);
+static BOOT_UNIX_TIME: AtomicU64 = AtomicU64::new(0);
+
+/// Advance the monotonic tick counter by one tick
+///
+/// Called from the kernel's main loop, once per timer interrupt.
+pub fn tick() {
+    MONOTONIC_TICKS.fetch_add(1, Ordering::Relaxed);
+}
+
+/// Ticks elapsed since boot
+pub fn monotonic_ticks() -> u64 {
+    MONOTONIC_TICKS.load(Ordering::Relaxed)
+}
+
+/// Record the wall-clock time at boot, as reported by the hypervisor or firmware
+pub fn set_boot_unix_time(unix_time: u64) {
+    BOOT_UNIX_TIME.store(unix_time, Ordering::Relaxed);
+}
+
+/// Current Unix timestamp, approximated as boot time plus ticks elapsed
+pub fn now_unix() -> u64 {
+    BOOT_UNIX_TIME.load(Ordering::Relaxed) + monotonic_ticks()
+}
+
+/// Initialize the time subsystem
+pub fn init() {
+    crate::println!("Initializing kernel time subsystem...");
+    crate::println!("Kernel time subsystem initialized");
+}